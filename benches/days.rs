@@ -0,0 +1,172 @@
+//! Parse and solve of every day, and the hot paths of some of them, on the
+//! example and real inputs. Missing inputs are skipped, so the real ones are
+//! only measured where they were downloaded.
+//!
+//! Save a baseline with `cargo bench -- --save-baseline <name>` and compare
+//! another commit with `cargo bench -- --baseline <name>`.
+
+use aoc2023::config::{Config, CONFIG_FILE};
+use aoc2023::input::Input;
+use aoc2023::solver::{Solver, DEFAULT_STRATEGY};
+use aoc2023::util::input_path;
+use aoc2023::{day10, day11, day12, day3, day4, day5, day6, day7, registry};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use std::path::Path;
+
+const VARIANTS: [&str; 2] = ["test", "real"];
+
+/// The inputs directory and the parameters of the days come from
+/// `aoc.toml`, like when running the days
+fn load_config() -> Config {
+    Config::load(CONFIG_FILE).unwrap_or_else(|e| panic!("{}", e))
+}
+
+fn read_input(config: &Config, day: u8, variant: &str) -> Option<Vec<String>> {
+    let path = input_path(&config.inputs, day, variant);
+    if !Path::new(&path).exists() {
+        return None;
+    }
+
+    Some(Input::open(path).and_then(Input::lines).unwrap())
+}
+
+fn parse<S: Solver>(solver: &S, lines: &[String]) -> S::Input {
+    solver.parse(lines.iter().cloned()).unwrap()
+}
+
+fn bench_every_day(c: &mut Criterion) {
+    let config = load_config();
+    let mut solvers = registry();
+    config.configure(&mut solvers).unwrap();
+
+    for solver in solvers {
+        for variant in VARIANTS {
+            let Some(lines) = read_input(&config, solver.day(), variant) else {
+                continue;
+            };
+
+            let mut group = c.benchmark_group(format!("day{}/{}", solver.day(), variant));
+            if variant == "real" {
+                group.sample_size(10);
+            }
+
+            group.bench_function("parse", |b| {
+                b.iter(|| solver.parse(&mut lines.iter().cloned()).unwrap())
+            });
+
+            let input = solver.parse(&mut lines.iter().cloned()).unwrap();
+            for part in [1, 2] {
+                group.bench_function(format!("part{}", part), |b| {
+                    b.iter(|| solver.solve_with(part, DEFAULT_STRATEGY, black_box(&*input)))
+                });
+            }
+
+            group.finish();
+        }
+    }
+}
+
+fn bench_hot_paths(c: &mut Criterion) {
+    let config = load_config();
+
+    for variant in VARIANTS {
+        let mut group = c.benchmark_group(format!("hot/{}", variant));
+        if variant == "real" {
+            group.sample_size(10);
+        }
+
+        if let Some(lines) = read_input(&config, 3, variant) {
+            let schematic = parse(&day3::Day3, &lines);
+            group.bench_function("day3 retrieve_part_numbers_for_all_gears", |b| {
+                b.iter_batched(
+                    || {
+                        (
+                            schematic.retrieve_possible_gears_position(),
+                            schematic.retrieve_numbers_and_positions(),
+                        )
+                    },
+                    |(gears, nums)| schematic.retrieve_part_numbers_for_all_gears(gears, nums),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 4, variant) {
+            let cards = parse(&day4::Day4, &lines);
+            group.bench_function("day4 process_all_cards", |b| {
+                b.iter(|| day4::process_all_cards(black_box(&cards)))
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 5, variant) {
+            let seed_file = parse(&day5::Day5, &lines);
+            group.bench_function("day5 seed_to_location_ranges", |b| {
+                b.iter(|| {
+                    seed_file
+                        .seed_ranges()
+                        .unwrap()
+                        .into_iter()
+                        .flat_map(|s| seed_file.seed_to_location_ranges(s))
+                        .count()
+                })
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 6, variant) {
+            let (races, long_races) = parse(&day6::Day6, &lines);
+            group.bench_function("day6 victories_for_each_record", |b| {
+                b.iter(|| {
+                    races
+                        .iter()
+                        .chain(&long_races)
+                        .map(|r| day6::victories_for_each_record(*r).len())
+                        .sum::<usize>()
+                })
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 7, variant) {
+            let hand_bids = parse(&day7::Day7, &lines);
+            group.bench_function("day7 sort by order_card_hand", |b| {
+                b.iter_batched(
+                    || hand_bids.clone(),
+                    |mut hands| {
+                        hands.sort_by(|(l, _), (r, _)| day7::order_card_hand(l, r, true));
+                        hands
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 10, variant) {
+            let map = parse(&day10::Day10, &lines);
+            group.bench_function("day10 retrieve_map_distances", |b| {
+                b.iter(|| map.retrieve_map_distances())
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 11, variant) {
+            // the same scale as the day11 benches, from aoc.toml
+            let mut day11 = day11::Day11::default();
+            config.configure_day(&mut day11).unwrap();
+            let galaxies = parse(&day11, &lines);
+            let map = day11::GalacticMap::from_galaxies(day11.scale, &galaxies);
+            group.bench_function("day11 generate_distances", |b| {
+                b.iter(|| map.generate_distances())
+            });
+        }
+
+        if let Some(lines) = read_input(&config, 12, variant) {
+            let springs = parse(&day12::Day12, &lines);
+            group.bench_function("day12 generate_permutation_sum", |b| {
+                b.iter(|| springs.generate_permutation_sum())
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_every_day, bench_hot_paths);
+criterion_main!(benches);
@@ -0,0 +1,357 @@
+use crate::input::InputSource;
+use crate::runner::{run_input, Outcome};
+use crate::solver::DynSolver;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// What we know about an answer, usually from the site response
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnswerStatus {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+}
+
+impl AnswerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnswerStatus::Right => "right",
+            AnswerStatus::Wrong => "wrong",
+            AnswerStatus::TooHigh => "too-high",
+            AnswerStatus::TooLow => "too-low",
+        }
+    }
+}
+
+impl TryFrom<&str> for AnswerStatus {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "right" => Ok(AnswerStatus::Right),
+            "wrong" => Ok(AnswerStatus::Wrong),
+            "too-high" => Ok(AnswerStatus::TooHigh),
+            "too-low" => Ok(AnswerStatus::TooLow),
+            x => Err(format!(
+                "Invalid answer status: {} (expected right, wrong, too-high or too-low)",
+                x
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    pub status: AnswerStatus,
+}
+
+impl KnownAnswer {
+    /// Parse a line in the form `<day> <part> <input> <answer> [status]`.
+    /// Without a status, the answer is the right one.
+    fn from_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        let (day, part, input, answer, status) = match fields[..] {
+            [day, part, input, answer] => (day, part, input, answer, "right"),
+            [day, part, input, answer, status] => (day, part, input, answer, status),
+            _ => {
+                return Err(String::from(
+                    "expected <day> <part> <input> <answer> [status]",
+                ))
+            }
+        };
+
+        let day = day.parse().map_err(|_| format!("Invalid day: {}", day))?;
+        let part = match part {
+            "1" => 1,
+            "2" => 2,
+            x => return Err(format!("Invalid part: {} (expected 1 or 2)", x)),
+        };
+
+        Ok(KnownAnswer {
+            day,
+            part,
+            input: input.to_string(),
+            answer: answer.to_string(),
+            status: AnswerStatus::try_from(status)?,
+        })
+    }
+}
+
+impl KnownAnswer {
+    /// Line for the answers file, without the status when it is right
+    pub fn to_line(&self) -> String {
+        match self.status {
+            AnswerStatus::Right => {
+                format!("{} {} {} {}", self.day, self.part, self.input, self.answer)
+            }
+            status => format!(
+                "{} {} {} {} {}",
+                self.day,
+                self.part,
+                self.input,
+                self.answer,
+                status.as_str()
+            ),
+        }
+    }
+}
+
+/// Parse an answers file. Empty lines and lines starting with `#` are
+/// ignored.
+pub fn parse_answers(text: &str) -> Result<Vec<KnownAnswer>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .map(|(index, l)| {
+            KnownAnswer::from_line(l).map_err(|e| format!("line {}: {}", index + 1, e))
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    /// We only know wrong answers, and this is not one of them
+    Unknown,
+}
+
+/// Compare numbers as numbers, so `too-high`/`too-low` can rule out
+/// answers we never submitted
+fn compare_answers(a: &str, b: &str) -> Option<Ordering> {
+    match (a.parse::<i128>(), b.parse::<i128>()) {
+        (Ok(a), Ok(b)) => Some(a.cmp(&b)),
+        _ => None,
+    }
+}
+
+/// Check `answer` against everything we know about the same day, part and
+/// input.
+pub fn judge(answer: &str, known: &[&KnownAnswer]) -> Verdict {
+    for k in known {
+        let ordering = compare_answers(answer, &k.answer);
+        let ruled_out = match k.status {
+            AnswerStatus::Right => false,
+            AnswerStatus::Wrong => answer == k.answer,
+            AnswerStatus::TooHigh => answer == k.answer || ordering == Some(Ordering::Greater),
+            AnswerStatus::TooLow => answer == k.answer || ordering == Some(Ordering::Less),
+        };
+
+        if ruled_out {
+            return Verdict::Fail(format!(
+                "known wrong answer ({} is {})",
+                k.answer,
+                k.status.as_str()
+            ));
+        }
+    }
+
+    match known.iter().find(|k| k.status == AnswerStatus::Right) {
+        Some(k) if k.answer == answer => Verdict::Pass,
+        Some(k) => Verdict::Fail(format!("expected {}", k.answer)),
+        None => Verdict::Unknown,
+    }
+}
+
+/// Run every day, part and input in the answers file, and check the answers.
+/// Returns if everything passed.
+pub fn verify(solvers: &[Box<dyn DynSolver>], answers_path: &str) -> bool {
+    let known = match std::fs::read_to_string(answers_path).map_err(|e| e.to_string()) {
+        Ok(text) => parse_answers(&text),
+        Err(e) => Err(e),
+    };
+    let known = match known {
+        Ok(known) => known,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", answers_path, e);
+            return false;
+        }
+    };
+
+    let mut by_input: BTreeMap<(u8, &str), Vec<&KnownAnswer>> = BTreeMap::new();
+    for k in &known {
+        by_input.entry((k.day, &k.input)).or_default().push(k);
+    }
+
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    for ((day, input), known) in by_input {
+        let mut parts: Vec<u8> = known.iter().map(|k| k.part).collect();
+        parts.sort();
+        parts.dedup();
+
+        let Some(solver) = solvers.iter().find(|s| s.day() == day) else {
+            for part in parts {
+                println!(
+                    "FAIL  day {:<2} part {}  {:<28}  no solver for this day",
+                    day, part, input
+                );
+                failed += 1;
+            }
+            continue;
+        };
+
+        let run = run_input(
+            solver.as_ref(),
+            &InputSource::File(input.to_string()),
+            &parts,
+        );
+
+        for part in run.parts {
+            let known: Vec<&KnownAnswer> = known
+                .iter()
+                .filter(|k| k.part == part.part)
+                .cloned()
+                .collect();
+
+            let (result, detail) = match part.outcome {
+                Outcome::Answer(answer) => match judge(&answer.to_string(), &known) {
+                    Verdict::Pass => ("PASS", answer.to_string()),
+                    Verdict::Unknown => ("????", format!("{} (no known right answer)", answer)),
+                    Verdict::Fail(reason) => ("FAIL", format!("{}: {}", answer, reason)),
+                },
+                Outcome::MissingInput => ("SKIP", String::from("missing input")),
+                Outcome::NotSolved => ("FAIL", String::from("not solved yet")),
+                Outcome::InvalidInput(e) => ("FAIL", format!("invalid input: {}", e)),
+                Outcome::Panicked => ("FAIL", String::from("panicked")),
+            };
+
+            match result {
+                "PASS" => passed += 1,
+                "FAIL" => failed += 1,
+                _ => skipped += 1,
+            }
+
+            println!(
+                "{}  day {:<2} part {}  {:<28}  {}",
+                result, day, part.part, input, detail
+            );
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} skipped or unknown",
+        passed, failed, skipped
+    );
+    failed == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "\
+# day part input answer [status]
+5 1 inputs/day5test.txt 35
+
+5 2 inputs/day5real.txt 100 too-high
+5 2 inputs/day5real.txt 10 too-low
+5 2 inputs/day5real.txt 42 wrong
+7 1 inputs/day7real.txt abc wrong
+";
+
+    fn known(day: u8, part: u8) -> Vec<KnownAnswer> {
+        parse_answers(ANSWERS)
+            .unwrap()
+            .into_iter()
+            .filter(|k| k.day == day && k.part == part)
+            .collect()
+    }
+
+    #[test]
+    fn every_status_is_parsed_and_written_again() {
+        let answers = parse_answers(ANSWERS).unwrap();
+
+        let statuses: Vec<AnswerStatus> = answers.iter().map(|k| k.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                AnswerStatus::Right,
+                AnswerStatus::TooHigh,
+                AnswerStatus::TooLow,
+                AnswerStatus::Wrong,
+                AnswerStatus::Wrong
+            ]
+        );
+        assert_eq!(answers[0].to_line(), "5 1 inputs/day5test.txt 35");
+        assert_eq!(answers[1].to_line(), "5 2 inputs/day5real.txt 100 too-high");
+    }
+
+    #[test]
+    fn malformed_lines_are_errors_with_their_line_number() {
+        for (text, error) in [
+            (
+                "5 1 input",
+                "line 1: expected <day> <part> <input> <answer> [status]",
+            ),
+            (
+                "\n5 1 input 1 right extra",
+                "line 2: expected <day> <part> <input> <answer> [status]",
+            ),
+            ("# comment\nfive 1 input 1", "line 2: Invalid day: five"),
+            ("5 3 input 1", "line 1: Invalid part: 3 (expected 1 or 2)"),
+            (
+                "5 1 input 1\n5 1 input 2 maybe",
+                "line 2: Invalid answer status: maybe (expected right, wrong, too-high or too-low)",
+            ),
+        ] {
+            assert_eq!(parse_answers(text), Err(String::from(error)), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn the_right_answer_passes_and_others_fail() {
+        let known = known(5, 1);
+        let known: Vec<&KnownAnswer> = known.iter().collect();
+
+        assert_eq!(judge("35", &known), Verdict::Pass);
+        assert_eq!(
+            judge("36", &known),
+            Verdict::Fail(String::from("expected 35"))
+        );
+    }
+
+    #[test]
+    fn answers_are_ruled_out_by_what_is_known_to_be_wrong() {
+        let known = known(5, 2);
+        let known: Vec<&KnownAnswer> = known.iter().collect();
+
+        // not the same as a too-high answer, but higher
+        assert_eq!(
+            judge("150", &known),
+            Verdict::Fail(String::from("known wrong answer (100 is too-high)"))
+        );
+        assert!(matches!(judge("100", &known), Verdict::Fail(_)));
+        assert_eq!(
+            judge("9", &known),
+            Verdict::Fail(String::from("known wrong answer (10 is too-low)"))
+        );
+        assert!(matches!(judge("42", &known), Verdict::Fail(_)));
+
+        // between the bounds, and not known to be wrong
+        assert_eq!(judge("50", &known), Verdict::Unknown);
+        assert_eq!(judge("11", &known), Verdict::Unknown);
+    }
+
+    #[test]
+    fn answers_that_are_not_numbers_are_only_compared_as_text() {
+        let known = known(7, 1);
+        let known: Vec<&KnownAnswer> = known.iter().collect();
+        assert!(matches!(judge("abc", &known), Verdict::Fail(_)));
+        assert_eq!(judge("abd", &known), Verdict::Unknown);
+
+        let too_high = KnownAnswer {
+            answer: String::from("100"),
+            status: AnswerStatus::TooHigh,
+            ..known[0].clone()
+        };
+        assert_eq!(judge("abc", &[&too_high]), Verdict::Unknown);
+        assert_eq!(compare_answers("abc", "100"), None);
+        assert_eq!(compare_answers("-5", "3"), Some(Ordering::Less));
+    }
+}
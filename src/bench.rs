@@ -0,0 +1,235 @@
+use crate::error::ParseError;
+use crate::input::InputSource;
+use crate::solver::{Answer, DynSolver};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Statistics of the times of repeated runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to compute statistics");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let middle = sorted.len() / 2;
+        let median = match sorted.len() % 2 {
+            0 => (sorted[middle - 1] + sorted[middle]) / 2,
+            _ => sorted[middle],
+        };
+
+        let secs: Vec<f64> = sorted.iter().map(|d| d.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / secs.len() as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Run `f` `warmup` times without measuring, and then `runs` times
+pub fn measure<T>(runs: usize, warmup: usize, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..warmup {
+        black_box(f());
+    }
+
+    let samples: Vec<Duration> = (0..runs.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+
+    Stats::from_samples(&samples)
+}
+
+#[derive(Debug)]
+pub struct BenchRow {
+    /// `parse`, or the part and strategy
+    pub label: String,
+    pub answer: Option<Answer>,
+    pub stats: Stats,
+}
+
+/// Benchmark the parsing of `lines` and each part with each of the chosen
+/// strategies. The input is parsed again on every run of the parser, but
+/// the parts are all solved from the same parsed input.
+pub fn bench_day(
+    solver: &dyn DynSolver,
+    lines: &[String],
+    parts: &[(u8, Vec<&str>)],
+    runs: usize,
+    warmup: usize,
+) -> Result<Vec<BenchRow>, ParseError> {
+    let input = solver.parse(&mut lines.iter().cloned())?;
+
+    let mut rows = vec![BenchRow {
+        label: String::from("parse"),
+        answer: None,
+        stats: measure(runs, warmup, || solver.parse(&mut lines.iter().cloned())),
+    }];
+
+    for (part, strategies) in parts {
+        for strategy in strategies {
+            let answer = solver.solve_with(*part, strategy, &*input);
+            let stats = measure(runs, warmup, || solver.solve_with(*part, strategy, &*input));
+
+            rows.push(BenchRow {
+                label: format!("part {} {}", part, strategy),
+                answer,
+                stats,
+            });
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Strategies chosen with `--strategy` for each part, besides the default.
+/// Names that do not exist for any of the parts are an error.
+pub fn chosen_strategies<'a>(
+    solver: &'a dyn DynSolver,
+    parts: &[u8],
+    chosen: &[String],
+) -> Result<Vec<(u8, Vec<&'a str>)>, String> {
+    let all = chosen.iter().any(|c| c == "all");
+    let available: Vec<(u8, Vec<&str>)> =
+        parts.iter().map(|&p| (p, solver.strategies(p))).collect();
+
+    if let Some(unknown) = chosen.iter().find(|c| {
+        *c != "all"
+            && !available
+                .iter()
+                .any(|(_, names)| names.contains(&c.as_str()))
+    }) {
+        let names: Vec<String> = available
+            .iter()
+            .flat_map(|(p, names)| names.iter().map(move |n| format!("{} (part {})", n, p)))
+            .collect();
+        return Err(format!(
+            "Unknown strategy: {}. Day {} has: {}",
+            unknown,
+            solver.day(),
+            names.join(", ")
+        ));
+    }
+
+    Ok(available
+        .into_iter()
+        .map(|(part, names)| {
+            let names = names
+                .into_iter()
+                .enumerate()
+                .filter(|&(i, n)| i == 0 || all || chosen.iter().any(|c| c == n))
+                .map(|(_, n)| n)
+                .collect();
+            (part, names)
+        })
+        .collect())
+}
+
+/// Benchmark a day on an input, with the default and the chosen strategies,
+/// and print the table
+pub fn report(
+    solver: &dyn DynSolver,
+    input: &InputSource,
+    parts: &[u8],
+    chosen: &[String],
+    runs: usize,
+    warmup: usize,
+) -> Result<(), String> {
+    let strategies = chosen_strategies(solver, parts, chosen)?;
+    let lines = input.open(solver.day())?.lines()?;
+
+    println!(
+        "day {} ({}), {} runs after {} warm-up runs\n",
+        solver.day(),
+        solver.name(),
+        runs,
+        warmup
+    );
+
+    let rows = bench_day(solver, &lines, &strategies, runs, warmup)
+        .map_err(|e| format!("Invalid input: {}", e))?;
+    print_bench_table(&rows);
+    Ok(())
+}
+
+pub fn print_bench_table(rows: &[BenchRow]) {
+    println!(
+        "{:<24}  {:<20}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "", "Answer", "Min", "Median", "Mean", "Stddev"
+    );
+
+    for row in rows {
+        let answer = match &row.answer {
+            Some(answer) => answer.to_string(),
+            None if row.label == "parse" => String::new(),
+            None => String::from("(not solved yet)"),
+        };
+
+        println!(
+            "{:<24}  {:<20}  {:>12}  {:>12}  {:>12}  {:>12}",
+            row.label,
+            answer,
+            format!("{:.2?}", row.stats.min),
+            format!("{:.2?}", row.stats.median),
+            format!("{:.2?}", row.stats.mean),
+            format!("{:.2?}", row.stats.stddev),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_samples() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(&[ms(4), ms(1), ms(3), ms(2)]);
+
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        // sqrt(1.25) ms
+        assert_eq!(stats.stddev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn only_the_chosen_strategies_are_added_to_the_default() {
+        let solvers = crate::registry();
+        let day5 = solvers.iter().find(|s| s.day() == 5).unwrap().as_ref();
+        let chosen = |names: &[&str]| {
+            let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+            chosen_strategies(day5, &[1, 2], &names)
+        };
+
+        assert_eq!(
+            chosen(&[]).unwrap(),
+            vec![(1, vec!["default"]), (2, vec!["default"])]
+        );
+        assert_eq!(
+            chosen(&["brute-force"]).unwrap(),
+            vec![(1, vec!["default"]), (2, vec!["default", "brute-force"])]
+        );
+        assert_eq!(chosen(&["all"]).unwrap(), chosen(&["brute-force"]).unwrap());
+        assert_eq!(
+            chosen(&["fast"]).unwrap_err(),
+            "Unknown strategy: fast. Day 5 has: default (part 1), default (part 2), brute-force (part 2)"
+        );
+    }
+}
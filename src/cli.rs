@@ -0,0 +1,282 @@
+use crate::logging::parse_day_filter;
+use aoc2023::config::{Config, CONFIG_FILE};
+use aoc2023::input::InputSource;
+use aoc2023::runner::Format;
+use aoc2023::site::DEFAULT_BASE_URL;
+use aoc2023::submit::DEFAULT_LOG;
+use log::LevelFilter;
+use std::thread;
+
+pub const USAGE: &str = "\
+Usage: aoc2023 <command> [options]
+
+Commands:
+    list              list the available days
+    day<X> | <X>      run the solver for day X
+    all               run every day, and show a table with answers and timings
+    verify            check the answers against the answers file
+    bench <X>         run day X many times, and show statistics of the times
+    new <X>           create the module and input files of day X
+    watch <X>         run day X on the example and real inputs every time its
+                      module or inputs change (Linux only)
+    generate <X>      print a random input for day X
+    crosscheck [<X>]  solve random inputs with every strategy of the parts
+                      that have more than one, and show where they disagree
+    check <X> <FILE>  only parse FILE (`-` for stdin) as an input of day X,
+                      and show what is wrong with it
+    fetch <X>         download the input of day X to ./inputs/day<X>real.txt
+    submit <X> <1|2>  send the answer of a part of day X, unless it is known
+                      to be wrong, and save the response in the answers file
+    day8view          watch the day 8 map being walked
+
+Options:
+    -i, --input <PATH>    read the puzzle input from PATH (`-` for stdin)
+    -t, --test            use the example input (./inputs/day<X>test.txt)
+    --variant <NAME>      use ./inputs/day<X><NAME>.txt (default: real)
+    -p, --part <1|2|both> which part to run (default: both)
+    --answers <PATH>      answers file used by verify (default: answers.txt)
+    -j, --jobs <N>        how many days `all` runs at the same time (default:
+                          one per CPU)
+    --format <text|json>  print just the answers, or JSON with the timings
+                          (default: text)
+    -v, -vv               show the debug output of the solvers (-vv for the
+                          traces too)
+    -q, --quiet           only show errors
+    --log <DAY>[=<LEVEL>] show the logs of a single day, like `--log day5` or
+                          `--log 5=debug` (can be repeated)
+
+Bench options:
+    --runs <N>            measured runs (default: 100)
+    --warmup <N>          runs before measuring (default: 3)
+    --strategy <NAME>     also measure another way of solving the parts
+                          (can be repeated, `all` for every one)
+
+New options:
+    --name <NAME>         title of the puzzle
+
+Generate and crosscheck options:
+    --size <N>            how big the input is: lines, or the side of a map
+                          (the default depends on the day; crosscheck makes
+                          inputs from 1 to N, and the default is 3)
+    --seed <N>            seed of the random numbers (default: random)
+    --runs <N>            how many inputs crosscheck makes (default: 100)
+
+Fetch and submit options:
+    --url <URL>           address of the site (default: https://adventofcode.com)
+    --submissions <PATH>  log of every answer sent (default: submissions.log)
+
+The session token is read from AOC_SESSION, or from the .aoc-session file.
+
+The inputs directory, the default input and part, the answers file and the
+parameters of some days can be changed in aoc.toml.";
+
+#[derive(Debug)]
+pub enum Command {
+    List,
+    All,
+    Verify,
+    Day8View,
+    Bench(String),
+    New(u8),
+    Fetch(u8),
+    Submit(String, u8),
+    Watch(u8),
+    Check(String),
+    Generate(u8),
+    Crosscheck(Option<String>),
+    Run(String),
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub command: Command,
+    pub input: InputSource,
+    pub parts: Vec<u8>,
+    pub answers: String,
+    pub format: Format,
+    /// -1 when quiet, 1 or 2 for each -v
+    pub verbosity: i8,
+    pub log_filters: Vec<(u8, LevelFilter)>,
+    /// Threads used by `all`
+    pub jobs: usize,
+    pub runs: usize,
+    pub warmup: usize,
+    pub strategies: Vec<String>,
+    pub name: Option<String>,
+    pub url: String,
+    pub submissions: String,
+    pub size: Option<usize>,
+    pub seed: Option<u64>,
+}
+
+fn parse_parts(value: &str) -> Result<Vec<u8>, String> {
+    match value {
+        "1" => Ok(vec![1]),
+        "2" => Ok(vec![2]),
+        "both" => Ok(vec![1, 2]),
+        x => Err(format!("Invalid part: {} (expected 1, 2 or both)", x)),
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format, String> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        x => Err(format!("Invalid format: {} (expected text or json)", x)),
+    }
+}
+
+fn parse_day_number(value: &str) -> Result<u8, String> {
+    match value.trim_start_matches("day").parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(format!("Invalid day: {} (expected 1 to 25)", value)),
+    }
+}
+
+fn parse_count(option: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", option, value))
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid seed: {} (expected 0 to {})", value, u64::MAX))
+}
+
+fn option_value(option: &str, args: &mut impl Iterator<Item = String>) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("Option {} needs a value", option))
+}
+
+/// The defaults of the options come from the config file
+pub fn parse_args(args: impl Iterator<Item = String>, config: &Config) -> Result<Args, String> {
+    let mut args = args.peekable();
+    let command = args
+        .next()
+        .ok_or("You need to pass the day (in the form of day<X>)")?;
+
+    // the commands with an argument
+    let day_arg = match command.as_str() {
+        "bench" | "new" | "fetch" | "submit" | "watch" | "check" | "generate" => Some(
+            args.next()
+                .ok_or_else(|| format!("`{}` needs a day", command))?,
+        ),
+        // the day is optional
+        "crosscheck" => args.next_if(|arg| !arg.starts_with('-')),
+        _ => None,
+    };
+    let submit_part = match command.as_str() {
+        "submit" => match args.next().as_deref() {
+            Some("1") => 1,
+            Some("2") => 2,
+            _ => return Err(String::from("`submit` needs a day and a part (1 or 2)")),
+        },
+        _ => 0,
+    };
+    let check_file = match command.as_str() {
+        "check" => Some(args.next().ok_or("`check` needs a day and a file")?),
+        _ => None,
+    };
+
+    let variant = |variant: String| InputSource::Variant {
+        dir: config.inputs.clone(),
+        variant,
+    };
+
+    let mut input = variant(config.variant.clone());
+    let mut parts = parse_parts(&config.part).map_err(|e| format!("{}: {}", CONFIG_FILE, e))?;
+    let mut answers = config.answers.clone();
+    let mut format = Format::Text;
+    let mut verbosity = 0;
+    let mut log_filters = vec![];
+    let mut jobs = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut runs = 100;
+    let mut warmup = 3;
+    let mut strategies = vec![];
+    let mut name = None;
+    let mut url = String::from(DEFAULT_BASE_URL);
+    let mut submissions = String::from(DEFAULT_LOG);
+    let mut size = None;
+    let mut seed = None;
+    if let Some(file) = check_file {
+        input = match file.as_str() {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(path.to_string()),
+        };
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--input" => {
+                input = match option_value(&arg, &mut args)?.as_str() {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(path.to_string()),
+                }
+            }
+            "-t" | "--test" => input = variant(String::from("test")),
+            "--variant" => input = variant(option_value(&arg, &mut args)?),
+            "-p" | "--part" => parts = parse_parts(&option_value(&arg, &mut args)?)?,
+            "--answers" => answers = option_value(&arg, &mut args)?,
+            "--format" => format = parse_format(&option_value(&arg, &mut args)?)?,
+            "-v" | "--verbose" => verbosity = (verbosity.max(0) + 1).min(2),
+            "-vv" => verbosity = 2,
+            "-q" | "--quiet" => verbosity = -1,
+            "--log" => log_filters.push(parse_day_filter(&option_value(&arg, &mut args)?)?),
+            "-j" | "--jobs" => match parse_count(&arg, &option_value(&arg, &mut args)?)? {
+                0 => return Err(String::from("--jobs needs at least 1")),
+                n => jobs = n,
+            },
+            "--runs" => runs = parse_count(&arg, &option_value(&arg, &mut args)?)?,
+            "--warmup" => warmup = parse_count(&arg, &option_value(&arg, &mut args)?)?,
+            "--strategy" => strategies.push(option_value(&arg, &mut args)?),
+            "--name" => name = Some(option_value(&arg, &mut args)?),
+            "--url" => url = option_value(&arg, &mut args)?,
+            "--submissions" => submissions = option_value(&arg, &mut args)?,
+            "--size" => size = Some(parse_count(&arg, &option_value(&arg, &mut args)?)?),
+            "--seed" => seed = Some(parse_seed(&option_value(&arg, &mut args)?)?),
+            x => return Err(format!("Unknown option: {}", x)),
+        }
+    }
+
+    let command = match command.as_str() {
+        "list" => Command::List,
+        "all" => match input {
+            InputSource::Variant { .. } => Command::All,
+            _ => return Err(String::from("`all` only accepts --test or --variant")),
+        },
+        "verify" => Command::Verify,
+        "day8view" => Command::Day8View,
+        "bench" => Command::Bench(day_arg.unwrap_or_default()),
+        "new" => Command::New(parse_day_number(&day_arg.unwrap_or_default())?),
+        "watch" => Command::Watch(parse_day_number(&day_arg.unwrap_or_default())?),
+        "fetch" => Command::Fetch(parse_day_number(&day_arg.unwrap_or_default())?),
+        "submit" => match input {
+            InputSource::Stdin => return Err(String::from("`submit` needs an input file")),
+            _ => Command::Submit(day_arg.unwrap_or_default(), submit_part),
+        },
+        "check" => Command::Check(day_arg.unwrap_or_default()),
+        "crosscheck" => Command::Crosscheck(day_arg),
+        "generate" => Command::Generate(parse_day_number(&day_arg.unwrap_or_default())?),
+        day => Command::Run(day.to_string()),
+    };
+
+    Ok(Args {
+        command,
+        input,
+        parts,
+        answers,
+        format,
+        verbosity,
+        log_filters,
+        jobs,
+        runs,
+        warmup,
+        strategies,
+        name,
+        url,
+        submissions,
+        size,
+        seed,
+    })
+}
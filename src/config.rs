@@ -0,0 +1,169 @@
+use crate::solver::{DynSolver, Solver};
+use crate::util::INPUTS_DIR;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Settings read from `aoc.toml`. Everything is optional, and the defaults
+/// are the same as without the file.
+///
+/// ```toml
+/// inputs = "./inputs"
+/// variant = "real"
+/// part = "both"
+/// answers = "answers.txt"
+///
+/// [day11]
+/// scale = 1000000
+/// ```
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Directory with the input files
+    pub inputs: String,
+    /// Input used when no other is chosen, like `real` or `test`
+    pub variant: String,
+    /// Part that runs when no other is chosen: `1`, `2` or `both`
+    pub part: String,
+    /// File with the expected answers
+    pub answers: String,
+    /// Parameters of each day, from the `[day<N>]` tables
+    #[serde(flatten)]
+    pub days: BTreeMap<String, toml::Value>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            inputs: String::from(INPUTS_DIR),
+            variant: String::from("real"),
+            part: String::from("both"),
+            answers: String::from("answers.txt"),
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(text).map_err(|e| e.message().to_string())?;
+
+        // the day tables are flattened, so anything unknown ends up there
+        for (key, value) in &config.days {
+            match (Config::day_of(key), value) {
+                (Some(_), toml::Value::Table(_)) => {}
+                (Some(_), _) => return Err(format!("[{}] should be a table", key)),
+                (None, _) => return Err(format!("Unknown setting: {}", key)),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Read the config file, or use the defaults when there is none
+    pub fn load(path: &str) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Could not read {}: {}", path, e)),
+        }
+    }
+
+    fn day_of(key: &str) -> Option<u8> {
+        key.strip_prefix("day")?.parse().ok()
+    }
+
+    /// Pass the parameters of each day to its solver
+    pub fn configure(&self, solvers: &mut [Box<dyn DynSolver>]) -> Result<(), String> {
+        for (key, params) in &self.days {
+            let (Some(day), toml::Value::Table(params)) = (Config::day_of(key), params) else {
+                continue;
+            };
+
+            let solver = solvers
+                .iter_mut()
+                .find(|s| s.day() == day)
+                .ok_or_else(|| format!("[{}]: there is no day {}", key, day))?;
+            solver
+                .configure(params)
+                .map_err(|e| format!("[{}]: {}", key, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Pass the parameters of its day to a single solver, like the benches
+    /// that call the functions of a day directly
+    pub fn configure_day<S: Solver>(&self, solver: &mut S) -> Result<(), String> {
+        for (key, params) in &self.days {
+            if let (Some(day), toml::Value::Table(params)) = (Config::day_of(key), params) {
+                if day == solver.day() {
+                    solver
+                        .configure(params)
+                        .map_err(|e| format!("[{}]: {}", key, e))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day11::Day11;
+    use crate::day2::Day2;
+    use crate::registry;
+
+    const EXAMPLE_11: &str = include_str!("../inputs/day11test.txt");
+
+    #[test]
+    fn settings_missing_from_the_file_are_the_defaults() {
+        let config = Config::parse("variant = \"test\"\n[day11]\nscale = 10\n").unwrap();
+
+        assert_eq!(config.variant, "test");
+        assert_eq!(config.inputs, Config::default().inputs);
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn unknown_settings_are_errors() {
+        assert!(Config::parse("varaint = \"test\"").is_err());
+        assert!(Config::parse("day11 = 10").is_err());
+        assert!(Config::parse("part = 1").is_err());
+    }
+
+    #[test]
+    fn days_get_their_parameters() {
+        let mut solvers = registry();
+        let config = Config::parse("[day11]\nscale = 10\n").unwrap();
+        config.configure(&mut solvers).unwrap();
+
+        let day11 = solvers.iter().find(|s| s.day() == 11).unwrap();
+        let input = day11
+            .parse(&mut EXAMPLE_11.lines().map(String::from))
+            .unwrap();
+        assert_eq!(day11.part2(&*input), Some(1030.into()));
+
+        let mut day11 = Day11::default();
+        config.configure_day(&mut day11).unwrap();
+        assert_eq!(day11.scale, 10);
+        let mut day2 = Day2::default();
+        config.configure_day(&mut day2).unwrap();
+        assert_eq!(day2.bag.reds, Day2::default().bag.reds);
+
+        let mut solvers = registry();
+        for bad in [
+            "[day11]\nsclae = 10",
+            "[day1]\nscale = 10",
+            "[day30]\nscale = 1",
+        ] {
+            let config = Config::parse(bad).unwrap();
+            assert!(config.configure(&mut solvers).is_err(), "{}", bad);
+        }
+    }
+}
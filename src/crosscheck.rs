@@ -0,0 +1,254 @@
+//! Differential tests: the parts with more than one strategy are solved with
+//! all of them on generated inputs, and any disagreement is shrunk to the
+//! smallest input that still shows it.
+
+use crate::generate::{default_size, generate};
+use crate::runner::{format_outcome, Outcome};
+use crate::solver::DynSolver;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Answer of each strategy of a part
+pub type Answers = Vec<(&'static str, Outcome)>;
+
+#[derive(Debug)]
+pub struct Disagreement {
+    pub day: u8,
+    pub part: u8,
+    /// Size and seed of the generated input where it was found
+    pub size: usize,
+    pub seed: u64,
+    /// Smallest input found that still shows the disagreement
+    pub input: Vec<String>,
+    /// Empty when the parser rejected the generated input
+    pub answers: Answers,
+}
+
+/// Parts of a day that can be checked, because they have other strategies
+pub fn checked_parts(solver: &dyn DynSolver) -> Vec<u8> {
+    [1, 2]
+        .into_iter()
+        .filter(|&part| solver.strategies(part).len() > 1)
+        .collect()
+}
+
+/// Solve a part with every strategy, or `None` if the input does not parse
+pub fn solve_with_all(solver: &dyn DynSolver, part: u8, lines: &[String]) -> Option<Answers> {
+    let input = solver.parse(&mut lines.iter().cloned()).ok()?;
+
+    let answers = solver
+        .strategies(part)
+        .into_iter()
+        .map(|strategy| {
+            let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+                solver.solve_with(part, strategy, &*input)
+            }));
+            let outcome = match answer {
+                Ok(Some(answer)) => Outcome::Answer(answer),
+                Ok(None) => Outcome::NotSolved,
+                Err(_) => Outcome::Panicked,
+            };
+
+            (strategy, outcome)
+        })
+        .collect();
+
+    Some(answers)
+}
+
+fn disagrees(answers: &Answers) -> bool {
+    answers.windows(2).any(|w| w[0].1 != w[1].1)
+}
+
+/// Remove as many lines as possible from an input where the strategies
+/// disagree, while they still disagree. Big chunks of lines are tried
+/// first, then smaller ones, down to single lines.
+pub fn shrink(solver: &dyn DynSolver, part: u8, lines: &[String]) -> Vec<String> {
+    let still_disagrees = |lines: &[String]| {
+        solve_with_all(solver, part, lines).is_some_and(|answers| disagrees(&answers))
+    };
+
+    let mut lines = lines.to_vec();
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut removed_any = false;
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<String> = [&lines[..start], &lines[end..]].concat();
+
+            if still_disagrees(&candidate) {
+                lines = candidate;
+                removed_any = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if !removed_any {
+            chunk /= 2;
+        }
+    }
+
+    lines
+}
+
+/// Solve a part of a day with every strategy on `cases` generated inputs,
+/// with sizes from 1 to `max_size`, but never above the default size of the
+/// day, so the slow strategies still finish. Returns how many inputs were checked,
+/// or the first disagreement.
+pub fn crosscheck(
+    solver: &dyn DynSolver,
+    part: u8,
+    cases: usize,
+    max_size: usize,
+    seed: u64,
+) -> Result<usize, Disagreement> {
+    let max_size = max_size.min(default_size(solver.day()).unwrap_or(max_size));
+    let mut checked = 0;
+    for case in 0..cases {
+        let seed = seed.wrapping_add(case as u64);
+        let size = case % max_size.max(1) + 1;
+        let Some(lines) = generate(solver.day(), Some(size), seed) else {
+            break;
+        };
+
+        match solve_with_all(solver, part, &lines) {
+            Some(answers) if disagrees(&answers) => {
+                let input = shrink(solver, part, &lines);
+                let answers = solve_with_all(solver, part, &input).unwrap_or(answers);
+
+                return Err(Disagreement {
+                    day: solver.day(),
+                    part,
+                    size,
+                    seed,
+                    input,
+                    answers,
+                });
+            }
+            Some(_) => checked += 1,
+            // the generators only make valid inputs, but the parsers may
+            // not think so
+            None => {
+                return Err(Disagreement {
+                    day: solver.day(),
+                    part,
+                    size,
+                    seed,
+                    input: lines,
+                    answers: vec![],
+                })
+            }
+        }
+    }
+
+    Ok(checked)
+}
+
+/// Crosscheck every part of `solvers` that has more than one strategy, and
+/// print if they agree, or the smallest input where they do not. Returns if
+/// they always agreed.
+pub fn report(solvers: &[&dyn DynSolver], runs: usize, max_size: usize, seed: u64) -> bool {
+    let mut ok = true;
+
+    for solver in solvers {
+        for part in checked_parts(*solver) {
+            let strategies = solver.strategies(part).join(", ");
+            match crosscheck(*solver, part, runs, max_size, seed) {
+                Ok(checked) => println!(
+                    "day {} part {}: {} agree on {} inputs",
+                    solver.day(),
+                    part,
+                    strategies,
+                    checked
+                ),
+                Err(d) if d.answers.is_empty() => {
+                    println!(
+                        "day {} part {}: the input made with --size {} --seed {} does not parse:\n{}",
+                        d.day,
+                        d.part,
+                        d.size,
+                        d.seed,
+                        d.input.join("\n")
+                    );
+                    ok = false;
+                }
+                Err(d) => {
+                    println!(
+                        "day {} part {}: {} disagree on the input made with --size {} --seed {}",
+                        d.day, d.part, strategies, d.size, d.seed
+                    );
+                    for (strategy, outcome) in &d.answers {
+                        println!("    {:<16} {}", strategy, format_outcome(outcome));
+                    }
+                    println!(
+                        "smallest input where they disagree:\n{}",
+                        d.input.join("\n")
+                    );
+                    ok = false;
+                }
+            }
+        }
+    }
+
+    ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{parse_each_line, ParseError};
+    use crate::solver::{Answer, Solver, Strategy};
+
+    /// Sums numbers, with a strategy that is wrong when there is a 7
+    #[derive(Default)]
+    struct Sum;
+
+    impl Solver for Sum {
+        type Input = Vec<i64>;
+
+        fn day(&self) -> u8 {
+            9
+        }
+
+        fn name(&self) -> &'static str {
+            "Sum"
+        }
+
+        fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+            parse_each_line(lines, |line| {
+                line.parse()
+                    .map_err(|_| ParseError::in_piece(line, line, "a number"))
+            })
+        }
+
+        fn part1(&self, numbers: &Self::Input) -> Option<Answer> {
+            Some(numbers.iter().sum::<i64>().into())
+        }
+
+        fn strategies(&self, _part: u8) -> Vec<Strategy<Self::Input>> {
+            vec![("skips-sevens", |numbers| {
+                Some(numbers.iter().filter(|&&n| n != 7).sum::<i64>().into())
+            })]
+        }
+    }
+
+    #[test]
+    fn disagreements_are_shrunk_to_the_lines_that_matter() {
+        let lines: Vec<String> = ["1", "2", "7", "4", "5", "7", "6"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        assert_eq!(shrink(&Sum, 1, &lines), vec!["7"]);
+    }
+
+    #[test]
+    fn every_strategy_answers() {
+        let answers = solve_with_all(&Sum, 1, &[String::from("3")]).unwrap();
+        assert_eq!(answers.len(), 2);
+        assert!(!disagrees(&answers));
+
+        assert!(solve_with_all(&Sum, 1, &[String::from("x")]).is_none());
+    }
+}
@@ -1,25 +1,16 @@
 // https://adventofcode.com/2023/day/1
 
-use crate::util::read_file_as_text;
-use std::io::prelude::*;
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+use log::trace;
 
-fn _fix_incorrect_line(line: &str) -> u64 {
-    let first = line.chars().find(|c| c.is_ascii_digit()).unwrap();
-    let last = line.chars().rfind(|c| c.is_ascii_digit()).unwrap();
+/// The first and last digits of the line as a number, or `None` when the
+/// line has no digits
+pub fn fix_incorrect_line(line: &str) -> Option<u64> {
+    let first = line.chars().find(|c| c.is_ascii_digit())?;
+    let last = line.chars().rfind(|c| c.is_ascii_digit())?;
 
-    format!("{}{}", first, last).parse::<u64>().unwrap()
-}
-
-pub fn _day1t1() {
-    let coord_file = read_file_as_text("./inputs/day1real.txt");
-
-    println!(
-        "{}",
-        coord_file
-            .lines()
-            .map(|l| _fix_incorrect_line(&l.unwrap()))
-            .sum::<u64>()
-    )
+    format!("{}{}", first, last).parse::<u64>().ok()
 }
 
 fn try_convert_into_number(line_piece: &str) -> Option<(String, usize)> {
@@ -44,7 +35,7 @@ fn try_convert_into_number(line_piece: &str) -> Option<(String, usize)> {
     .map(|(num, name)| (num.to_string(), name.len()))
 }
 
-fn transform_line(line: &str) -> String {
+pub fn transform_line(line: &str) -> String {
     let linem = line.to_lowercase();
 
     let mut resline = String::new();
@@ -68,24 +59,111 @@ fn transform_line(line: &str) -> String {
     resline
 }
 
-fn fix_incorrect_line2(line: &str) -> u64 {
+pub fn fix_incorrect_line2(line: &str) -> Option<u64> {
     let tline = transform_line(line);
-    let first = tline.chars().find(|c| c.is_ascii_digit()).unwrap();
-    let last = tline.chars().rfind(|c| c.is_ascii_digit()).unwrap();
+    let first = tline.chars().find(|c| c.is_ascii_digit())?;
+    let last = tline.chars().rfind(|c| c.is_ascii_digit())?;
 
-    // println!("{}{}", first, last);
+    trace!("{} -> {}{}", line, first, last);
 
-    format!("{}{}", first, last).parse::<u64>().unwrap()
+    format!("{}{}", first, last).parse::<u64>().ok()
 }
 
-pub fn day1() {
-    let coord_file = read_file_as_text("./inputs/day1real.txt");
+/// A line is valid if it has a digit for at least one of the parts. Only
+/// ASCII letters and digits are allowed, as the names are found by index.
+fn check_line(line: &str) -> Result<String, ParseError> {
+    if let Some((x, c)) = line
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_alphanumeric())
+    {
+        return Err(ParseError::new(
+            x + 1,
+            "a letter or a digit",
+            format!("{:?}", c),
+        ));
+    }
+    if fix_incorrect_line2(line).is_none() {
+        return Err(ParseError::in_piece(
+            line,
+            line,
+            "a digit or the name of one",
+        ));
+    }
+
+    Ok(line.to_string())
+}
+
+#[derive(Default)]
+pub struct Day1;
+
+impl Solver for Day1 {
+    type Input = Vec<String>;
 
-    println!(
-        "{}",
-        coord_file
-            .lines()
-            .map(|l| fix_incorrect_line2(&l.unwrap()))
-            .sum::<u64>()
-    )
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        parse_each_line(lines, check_line)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Option<Answer> {
+        // lines with only names of digits are valid for part 2 only
+        let sum: Option<u64> = input.iter().map(|l| fix_incorrect_line(l)).sum();
+
+        Some(sum?.into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Option<Answer> {
+        let sum: Option<u64> = input.iter().map(|l| fix_incorrect_line2(l)).sum();
+
+        Some(sum?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day1test.txt");
+    const EXAMPLE2: &str = include_str!("../inputs/day1test2.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day1, EXAMPLE, 1), Some(Answer::Int(142)));
+    }
+
+    #[test]
+    fn part2_example2() {
+        assert_eq!(solve_example(&Day1, EXAMPLE2, 2), Some(Answer::Int(281)));
+    }
+
+    #[test]
+    fn transform_line_spells_numbers() {
+        // the last letter of each number is kept, as it can start the next one
+        assert_eq!(transform_line("two1nine"), "2o19e");
+        assert_eq!(transform_line("eightwothree"), "82o3e");
+        assert_eq!(transform_line("abcone2threexyz"), "abc1e23exyz");
+        assert_eq!(fix_incorrect_line2("eightwothree"), Some(83));
+        assert_eq!(fix_incorrect_line2("7pqrstsixteen"), Some(76));
+        assert_eq!(fix_incorrect_line2("oneight"), Some(18));
+    }
+
+    #[test]
+    fn lines_without_any_digit_are_errors() {
+        let parse = |text: &str| Day1.parse(text.lines().map(String::from));
+
+        let e = parse("1abc2\npqrstuvwx").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+        let e = parse("1abc2\na1b 2c").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (2, 4, "' '"));
+
+        // only the names of digits, so there is nothing for part 1
+        assert_eq!(solve_example(&Day1, EXAMPLE2, 1), None);
+    }
 }
@@ -1,9 +1,12 @@
-use crate::util::{generate_adjacencies, read_file_as_text};
+use crate::error::ParseError;
+use crate::solver::{Answer, Solver};
+use crate::util::{Direction, Grid, Position};
+use log::{debug, trace};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::io::prelude::*;
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum Tile {
+pub enum Tile {
     PipeVertical,   // north-south
     PipeHorizontal, // east-west
     PipeNorthEast,
@@ -14,16 +17,6 @@ enum Tile {
     Ground,
 }
 
-impl Tile {
-    fn is_pipe(&self) -> bool {
-        match self {
-            Self::PipeVertical | Self::PipeHorizontal | Self::PipeNorthEast => true,
-            Self::PipeNorthWest | Self::PipeEastSouth | Self::PipeWestSouth => true,
-            _ => false,
-        }
-    }
-}
-
 impl TryFrom<char> for Tile {
     type Error = String;
 
@@ -42,93 +35,83 @@ impl TryFrom<char> for Tile {
     }
 }
 
-type MapPosition = (usize, usize);
-
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    Top,
-    Bottom,
-    Left,
-    Right,
-    TopRight,
-    TopLeft,
-    BottomRight,
-    BottomLeft,
-}
-
-impl Direction {
-    fn from_comparation_with_neighbor(current: MapPosition, other: MapPosition) -> Direction {
-        let difference = (
-            current.0 as isize - other.0 as isize,
-            current.1 as isize - other.1 as isize,
-        );
-
-        match difference {
-            (-1, 0) => Direction::Bottom,
-            (1, 0) => Direction::Top,
-            (0, -1) => Direction::Right,
-            (0, 1) => Direction::Left,
-
-            (-1, 1) => Direction::BottomRight,
-            (-1, -1) => Direction::BottomLeft,
-            (1, 1) => Direction::TopLeft,
-            (1, -1) => Direction::TopRight,
-
-            (0, 0) => panic!("There is no difference"),
-            _ => panic!("Impossible!"),
-        }
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Tile::PipeVertical => '|',
+            Tile::PipeHorizontal => '-',
+            Tile::PipeNorthEast => 'L',
+            Tile::PipeNorthWest => 'J',
+            Tile::PipeWestSouth => '7',
+            Tile::PipeEastSouth => 'F',
+            Tile::Ground => '.',
+            Tile::Start => 'S',
+        };
+
+        write!(f, "{}", c)
     }
 }
 
+pub type MapPosition = Position;
+
 #[derive(Debug)]
-struct MapNodeElement {
-    start_distance: usize,
-    connections: Vec<MapPosition>,
+pub struct MapNodeElement {
+    pub start_distance: usize,
+    pub connections: Vec<MapPosition>,
 }
 
 #[derive(Debug)]
-struct Map {
-    tiles: Vec<Vec<Tile>>,
+pub struct Map {
+    pub tiles: Grid<Tile>,
 }
 
 impl Map {
-    fn from_lines(lines: impl Iterator<Item = String>) -> Map {
-        let tiles = lines
-            .map(|line| {
-                line.chars()
-                    .filter_map(|c| Tile::try_from(c).ok())
-                    .collect()
-            })
+    pub fn from_lines(lines: impl Iterator<Item = String>) -> Result<Map, ParseError> {
+        let tiles: Grid<Tile> = Grid::from_lines(lines, "a tile (|-LJ7F.S)")?;
+
+        // the loop is found from the start, so there must be exactly one
+        let starts: Vec<MapPosition> = tiles
+            .iter()
+            .filter(|(_, tile)| **tile == Tile::Start)
+            .map(|(pos, _)| pos)
             .collect();
+        match starts[..] {
+            [] => {
+                return Err(ParseError::at_end_of_input(
+                    tiles.height(),
+                    "a start tile (S)",
+                ))
+            }
+            [_] => {}
+            // reported at the second one, but listing all of them
+            [_, (y, x), ..] => {
+                let positions: Vec<String> = starts
+                    .iter()
+                    .map(|(y, x)| format!("line {} column {}", y + 1, x + 1))
+                    .collect();
+                let expected = format!(
+                    "a single start tile, but there are {} (at {})",
+                    starts.len(),
+                    positions.join(", ")
+                );
+
+                return Err(ParseError::new(x + 1, expected, "\"S\"").at_line(y + 1));
+            }
+        }
 
-        Self { tiles }
+        Ok(Self { tiles })
     }
 
     /// returns (y, x)
-    fn find_start(&self) -> MapPosition {
-        self.tiles
-            .iter()
-            .enumerate()
-            .find_map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .find_map(|(x, tile)| {
-                        if let Tile::Start = tile {
-                            return Some(x);
-                        } else {
-                            return None;
-                        }
-                    })
-                    .map(|x| (y, x))
-            })
-            .unwrap()
+    pub fn find_start(&self) -> MapPosition {
+        self.tiles.find(|tile| *tile == Tile::Start).unwrap()
     }
 
     fn is_connectable_horizontally(&self, current: Tile, other: Tile) -> bool {
         match (current, other) {
             (Tile::Ground, _) | (_, Tile::Ground) => false,
             (Tile::PipeHorizontal, Tile::PipeHorizontal) => true,
-            (Tile::Start, x) if (x == Tile::PipeHorizontal) => true,
+            (Tile::Start, Tile::PipeHorizontal) => true,
             (x, Tile::Start)
                 if (x == Tile::PipeHorizontal
                     || x == Tile::PipeEastSouth
@@ -155,7 +138,7 @@ impl Map {
         match (current, other) {
             (Tile::Ground, _) | (_, Tile::Ground) => false,
             (Tile::PipeVertical, Tile::PipeVertical) => true,
-            (Tile::Start, x) if (x == Tile::PipeVertical) => true,
+            (Tile::Start, Tile::PipeVertical) => true,
             (x, Tile::Start)
                 if (x == Tile::PipeVertical
                     || x == Tile::PipeWestSouth
@@ -180,25 +163,22 @@ impl Map {
 
     fn is_connectable(&self, current: Tile, other: Tile, direction: Direction) -> bool {
         match direction {
-            Direction::Top => self.is_connectable_vertically(other, current),
-            Direction::Bottom => self.is_connectable_vertically(current, other),
-            Direction::Left => self.is_connectable_horizontally(other, current),
-            Direction::Right => self.is_connectable_horizontally(current, other),
+            Direction::North => self.is_connectable_vertically(other, current),
+            Direction::South => self.is_connectable_vertically(current, other),
+            Direction::West => self.is_connectable_horizontally(other, current),
+            Direction::East => self.is_connectable_horizontally(current, other),
             _ => false,
         }
     }
 
     /// Only check if the neighbors of a certain tile connects
     fn what_neighbors_connects_to(&self, pos: MapPosition) -> Vec<MapPosition> {
-        let (y, x) = pos;
-        let adjs = generate_adjacencies(&self.tiles, x, y);
-
-        adjs.into_iter()
-            .filter(|npos| {
-                let (ny, nx) = npos;
-                let direction = Direction::from_comparation_with_neighbor((y, x), (*ny, *nx));
-                self.is_connectable(self.tiles[y][x], self.tiles[*ny][*nx], direction)
+        self.tiles
+            .neighbors(pos, &Direction::ORTHOGONAL)
+            .filter(|&(direction, npos)| {
+                self.is_connectable(self.tiles[pos], self.tiles[npos], direction)
             })
+            .map(|(_, npos)| npos)
             .collect()
     }
 
@@ -218,7 +198,7 @@ impl Map {
         )
     }
 
-    fn retrieve_map_distances(&self) -> HashMap<MapPosition, MapNodeElement> {
+    pub fn retrieve_map_distances(&self) -> HashMap<MapPosition, MapNodeElement> {
         let start = self.find_start();
         let mut pos_queue: VecDeque<(MapPosition, MapNodeElement)> = VecDeque::new();
         let mut ret: HashMap<MapPosition, MapNodeElement> = HashMap::new();
@@ -240,14 +220,14 @@ impl Map {
                 visited.insert(*conn);
             }
 
-            //eprintln!("-- {:?} {:?}", node.0, node.1);
+            trace!("{:?} {:?}", node.0, node.1);
             ret.insert(node.0, node.1);
         }
 
         ret
     }
 
-    fn retrieve_map_farthest(&self, posmap: &HashMap<MapPosition, MapNodeElement>) -> usize {
+    pub fn retrieve_map_farthest(&self, posmap: &HashMap<MapPosition, MapNodeElement>) -> usize {
         let (_, element) = posmap
             .iter()
             .max_by(|&(_, e1), &(_, e2)| e1.start_distance.cmp(&e2.start_distance))
@@ -257,20 +237,67 @@ impl Map {
     }
 }
 
-// 13639 = not
-// 15
-pub fn day10() {
-    let game_file = read_file_as_text("./inputs/day10real.txt").lines();
-    //let game_file = read_file_as_text("./inputs/day10test3.txt").lines();
-    let map = Map::from_lines(game_file.filter_map(|s| s.ok()));
-    let distances = map.retrieve_map_distances();
-
-    //println!("Hello {:?}", map);
-    println!("start @ {:?}", map.find_start());
-    println!(
-        "conn @ {:?}",
-        map.what_neighbors_connects_to(map.find_start())
-    );
-    //println!("distances @ {:?}", distances);
-    println!("\n{}", map.retrieve_map_farthest(&distances))
+#[derive(Default)]
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Map;
+
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn name(&self) -> &'static str {
+        "Pipe Maze"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        Map::from_lines(lines)
+    }
+
+    fn part1(&self, map: &Self::Input) -> Option<Answer> {
+        let distances = map.retrieve_map_distances();
+
+        debug!("start @ {:?}", map.find_start());
+        debug!(
+            "conn @ {:?}",
+            map.what_neighbors_connects_to(map.find_start())
+        );
+        trace!("distances @ {:?}", distances);
+        Some(map.retrieve_map_farthest(&distances).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day10test.txt");
+    const EXAMPLE2: &str = include_str!("../inputs/day10test2.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day10, EXAMPLE, 1), Some(Answer::Int(4)));
+    }
+
+    #[test]
+    fn part1_example2() {
+        assert_eq!(solve_example(&Day10, EXAMPLE2, 1), Some(Answer::Int(8)));
+    }
+
+    #[test]
+    fn there_is_a_single_start() {
+        let map = |text: &str| Map::from_lines(text.lines().map(String::from));
+
+        let e = map(".....\n.F-7.\n.|.|.\n.L-J.").unwrap_err();
+        assert_eq!((e.line, e.found.as_str()), (5, "end of input"));
+
+        let e = map(".....\n.S-7.\n.|.|.\n.L-S.").unwrap_err();
+        assert_eq!((e.line, e.column), (4, 4));
+        assert_eq!(
+            e.expected,
+            "a single start tile, but there are 2 (at line 2 column 2, line 4 column 4)"
+        );
+    }
 }
@@ -1,85 +1,110 @@
-use crate::util::{generate_adjacencies, read_file_as_text};
-use std::collections::{HashSet, HashMap};
-use std::io::prelude::*;
+use crate::error::ParseError;
+use crate::solver::{configure_from, Answer, Solver};
+use crate::util::Grid;
 use itertools::Itertools;
+use log::{debug, trace};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 
-type GalacticPosition = (usize, usize);
-type GalacticIndex = usize;
+pub type GalacticPosition = (usize, usize);
+pub type GalacticIndex = usize;
 
-type GalaxyPair = (GalacticIndex, GalacticIndex, usize);
+pub type GalaxyPair = (GalacticIndex, GalacticIndex, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Space {
+    Galaxy,
+    Empty,
+}
+
+impl TryFrom<char> for Space {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '#' => Ok(Space::Galaxy),
+            '.' => Ok(Space::Empty),
+            x => Err(format!("Invalid space: {}", x)),
+        }
+    }
+}
 
 #[derive(Debug)]
-struct GalacticMap {
-    galaxies: HashMap<GalacticIndex, GalacticPosition>,
+pub struct GalacticMap {
+    pub galaxies: HashMap<GalacticIndex, GalacticPosition>,
 }
 
 impl GalacticMap {
     fn find_axis_without_galaxies<F: Fn(&GalacticPosition) -> usize>(
         axis_filter: F,
-        gals: &Vec<GalacticPosition>,
+        gals: &[GalacticPosition],
     ) -> Vec<usize> {
         let gal_rows = gals.iter().map(axis_filter).collect::<HashSet<usize>>();
-        let ymax = *gal_rows.iter().max().unwrap();
+        // without galaxies there is nothing to expand
+        let Some(&ymax) = gal_rows.iter().max() else {
+            return vec![];
+        };
 
-        (0..=ymax).filter(|num| !gal_rows.contains(&num)).collect()
+        (0..=ymax).filter(|num| !gal_rows.contains(num)).collect()
     }
 
     fn transform_space_expansion(scale: usize, axis_without_gal: Vec<usize>) -> Vec<usize> {
-        let axis_max: usize = axis_without_gal.iter().max().cloned().unwrap() + 2;
+        // without empty rows or columns, nothing moves
+        let axis_max: usize = axis_without_gal.iter().max().map_or(1, |max| max + 2);
 
         (0..axis_max)
             .scan(0, |acc, index| {
                 let a = *acc;
                 if axis_without_gal.contains(&index) {
-                    let scale = 1 * (scale - 1);
-                    *acc = a + scale;
-                    Some(a + scale)
+                    // the empty row or column is already counted once, and
+                    // scales below 1 are rejected by `configure`
+                    let added = scale.saturating_sub(1);
+                    *acc = a + added;
+                    Some(a + added)
                 } else {
                     Some(a)
                 }
             })
             .collect::<Vec<usize>>()
     }
-    
-    fn find_distance(&self, from: GalacticIndex, to: GalacticIndex) -> GalaxyPair {
+
+    pub fn find_distance(&self, from: GalacticIndex, to: GalacticIndex) -> GalaxyPair {
         let (y1, x1) = self.galaxies.get(&from).unwrap();
         let (y2, x2) = self.galaxies.get(&to).unwrap();
 
         let manhattan_distance = (x1.abs_diff(*x2)) + (y1.abs_diff(*y2));
         (from, to, manhattan_distance)
     }
-    
-    fn from_lines(scale: usize, lines: impl Iterator<Item = String>) -> GalacticMap {
-        let galaxies = lines
-            .enumerate()
-            .map(|(y, row)| {
-                row.chars()
-                    .enumerate()
-                    .filter_map(move |(x, c)| match c {
-                        '#' => Some((y, x)),
-                        _ => None,
-                    })
-                    .collect::<Vec<GalacticPosition>>()
-            })
-            .flatten()
-            .collect();
 
-        let rows = GalacticMap::transform_space_expansion(scale, GalacticMap::find_axis_without_galaxies(
-            |(y, _)| *y,
-            &galaxies,
-        ));
-        let cols = GalacticMap::transform_space_expansion(scale, GalacticMap::find_axis_without_galaxies(
-            |(_, x)| *x,
-            &galaxies,
-        ));
+    pub fn find_galaxies(
+        lines: impl Iterator<Item = String>,
+    ) -> Result<Vec<GalacticPosition>, ParseError> {
+        let image: Grid<Space> = Grid::from_lines(lines, "a galaxy or empty space (# or .)")?;
+
+        Ok(image
+            .iter()
+            .filter(|(_, space)| **space == Space::Galaxy)
+            .map(|(pos, _)| pos)
+            .collect())
+    }
+
+    pub fn from_galaxies(scale: usize, galaxies: &[GalacticPosition]) -> GalacticMap {
+        let rows = GalacticMap::transform_space_expansion(
+            scale,
+            GalacticMap::find_axis_without_galaxies(|(y, _)| *y, galaxies),
+        );
+        let cols = GalacticMap::transform_space_expansion(
+            scale,
+            GalacticMap::find_axis_without_galaxies(|(_, x)| *x, galaxies),
+        );
 
-        eprintln!("g {:?}", galaxies);
-        eprintln!("r {:?} c {:?}", rows, cols);
+        debug!("galaxies {:?}", galaxies);
+        debug!("expanded rows {:?} columns {:?}", rows, cols);
 
         GalacticMap {
             galaxies: galaxies
-                .into_iter()
-                .map(|(gy, gx)| {
+                .iter()
+                .map(|&(gy, gx)| {
                     let xoff = cols[gx.min(cols.len() - 1)];
                     let yoff = rows[gy.min(rows.len() - 1)];
 
@@ -91,21 +116,138 @@ impl GalacticMap {
         }
     }
 
-    fn generate_distances(&self) -> Vec<GalaxyPair> {
+    pub fn generate_distances(&self) -> Vec<GalaxyPair> {
         let gnums = 1..=self.galaxies.len();
 
-        gnums.combinations(2).map(|r| self.find_distance(r[0], r[1])).collect()        
+        gnums
+            .combinations(2)
+            .map(|r| self.find_distance(r[0], r[1]))
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Day11 {
+    /// How many rows or columns each empty one becomes, in part 2
+    pub scale: usize,
+}
+
+impl Default for Day11 {
+    fn default() -> Self {
+        Day11 { scale: 1000000 }
+    }
+}
+
+impl Solver for Day11 {
+    type Input = Vec<GalacticPosition>;
+
+    fn day(&self) -> u8 {
+        11
+    }
+
+    fn name(&self) -> &'static str {
+        "Cosmic Expansion"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        GalacticMap::find_galaxies(lines)
+    }
+
+    fn part1(&self, galaxies: &Self::Input) -> Option<Answer> {
+        let map = GalacticMap::from_galaxies(2, galaxies);
+        trace!("{:?}", map);
+
+        let dists = map.generate_distances();
+        Some(dists.into_iter().map(|(_, _, d)| d).sum::<usize>().into())
+    }
+
+    fn part2(&self, galaxies: &Self::Input) -> Option<Answer> {
+        let map = GalacticMap::from_galaxies(self.scale, galaxies);
+        trace!("{:?}", map);
+
+        let dists = map.generate_distances();
+        Some(dists.into_iter().map(|(_, _, d)| d).sum::<usize>().into())
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> Result<(), String> {
+        let mut day = Day11::default();
+        configure_from(&mut day, params)?;
+
+        if day.scale < 1 {
+            return Err(format!(
+                "scale must be at least 1 (an empty row becomes that many rows), not {}",
+                day.scale
+            ));
+        }
+
+        *self = day;
+        Ok(())
     }
 }
 
-pub fn day11() {
-    //let game_file = read_file_as_text("./inputs/day11test.txt").lines();
-    let game_file = read_file_as_text("./inputs/day11real.txt").lines();
-    let map = GalacticMap::from_lines(1000000, game_file.into_iter().filter_map(|s| s.ok()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
 
-    println!("Hello {:?}", map);
-    println!("Distance {:?}", map.find_distance(5, 9));
+    const EXAMPLE: &str = include_str!("../inputs/day11test.txt");
 
-    let dists = map.generate_distances();
-    println!("{}", dists.into_iter().map(|(_, _, d)| d).sum::<usize>());
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            solve_example(&Day11::default(), EXAMPLE, 1),
+            Some(Answer::Int(374))
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            solve_example(&Day11::default(), EXAMPLE, 2),
+            Some(Answer::Int(82000210))
+        );
+    }
+
+    #[test]
+    fn sum_of_distances_with_other_scales() {
+        let galaxies = Day11::default()
+            .parse(EXAMPLE.lines().map(String::from))
+            .unwrap();
+        let sum = |scale| {
+            GalacticMap::from_galaxies(scale, &galaxies)
+                .generate_distances()
+                .into_iter()
+                .map(|(_, _, d)| d)
+                .sum::<usize>()
+        };
+
+        assert_eq!(sum(10), 1030);
+        assert_eq!(sum(100), 8410);
+    }
+
+    #[test]
+    fn images_without_galaxies_have_no_distances() {
+        let empty = "...\n...";
+        assert_eq!(
+            solve_example(&Day11::default(), empty, 1),
+            Some(Answer::Int(0))
+        );
+        assert_eq!(
+            solve_example(&Day11::default(), empty, 2),
+            Some(Answer::Int(0))
+        );
+    }
+
+    #[test]
+    fn scales_below_1_are_errors() {
+        let mut day = Day11::default();
+        let params = |text: &str| text.parse::<toml::Table>().unwrap();
+
+        assert!(day.configure(&params("scale = 0")).is_err());
+        assert_eq!(day.scale, Day11::default().scale);
+
+        day.configure(&params("scale = 1")).unwrap();
+        assert_eq!(day.scale, 1);
+    }
 }
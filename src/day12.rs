@@ -1,64 +1,88 @@
-use crate::util::{read_file_as_text, split_numbers_by_comma};
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+use crate::util::parse_numbers;
 use itertools::Itertools;
-use std::io::prelude::*;
+use log::{debug, trace};
 
 #[derive(Debug, PartialEq, Clone)]
-enum Spring {
+pub enum Spring {
     Operational,
     Damaged,
 }
 
 impl Spring {
-    fn from_char(c: char) -> Option<Self> {
+    /// `None` is an unknown spring (`?`)
+    fn from_char(c: char) -> Result<Option<Self>, String> {
         match c {
-            '.' => Some(Spring::Operational),
-            '#' => Some(Spring::Damaged),
-            '?' => None,
-            _ => panic!("Unknown char"),
+            '.' => Ok(Some(Spring::Operational)),
+            '#' => Ok(Some(Spring::Damaged)),
+            '?' => Ok(None),
+            _ => Err(format!("Unknown spring: {}", c)),
         }
     }
 }
 
-type DamagedSpringCount = usize;
+pub type DamagedSpringCount = usize;
 
-type SpringRow = (Vec<Option<Spring>>, Vec<DamagedSpringCount>);
+pub type SpringRow = (Vec<Option<Spring>>, Vec<DamagedSpringCount>);
 
 #[derive(Debug)]
-struct SpringMap {
-    rows: Vec<SpringRow>,
+pub struct SpringMap {
+    pub rows: Vec<SpringRow>,
 }
 
 impl SpringMap {
-    fn parse_spring_line(line: &str) -> SpringRow {
+    fn parse_spring_line(line: &str) -> Result<SpringRow, ParseError> {
         let mut parts = line.split(' ');
 
-        let springs = parts
+        // split always returns at least one piece
+        let springs: Vec<Option<Spring>> = parts
             .next()
             .unwrap()
             .chars()
-            .map(|c| Spring::from_char(c))
-            .collect();
-        let counts = split_numbers_by_comma(&parts.next().unwrap());
+            .enumerate()
+            .map(|(x, c)| {
+                Spring::from_char(c)
+                    .map_err(|_| ParseError::new(x + 1, "a spring (., # or ?)", format!("{:?}", c)))
+            })
+            .collect::<Result<_, _>>()?;
+        let counts: Vec<DamagedSpringCount> = match parts.next() {
+            Some(counts) => parse_numbers(line, counts, ',', "a count of damaged springs")?,
+            None => return Err(ParseError::at_end(line, "the damaged spring counts")),
+        };
+
+        // each group needs an operational spring between it and the next
+        let needed = counts.iter().sum::<usize>() + counts.len().saturating_sub(1);
+        if needed > springs.len() {
+            let counts_piece = &line[springs.len() + 1..];
+            return Err(ParseError::in_piece(
+                line,
+                counts_piece,
+                format!("counts that fit in {} springs", springs.len()),
+            ));
+        }
 
-        (springs, counts)
+        Ok((springs, counts))
     }
 
-    fn new(lines: impl Iterator<Item = String>) -> Self {
-        let rows = lines.map(|l| SpringMap::parse_spring_line(&l)).collect();
+    pub fn new(lines: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let rows = parse_each_line(lines, SpringMap::parse_spring_line)?;
 
-        SpringMap { rows }
+        Ok(SpringMap { rows })
     }
 
     fn verify_if_line_is_valid(&self, springs: &[Spring], counts: &[DamagedSpringCount]) -> bool {
-        let groups = springs.into_iter().group_by(|&s| *s == Spring::Damaged);
+        let groups = springs.iter().group_by(|&s| *s == Spring::Damaged);
         let mut citer = counts.iter();
-        
-        groups.into_iter()
+
+        groups
+            .into_iter()
             .filter_map(|(k, g)| match k {
                 false => None,
                 true => Some(g),
             })
-            .all(|g| g.count() == *citer.next().unwrap_or(&0)) && citer.next().is_none()
+            .all(|g| g.count() == *citer.next().unwrap_or(&0))
+            && citer.next().is_none()
     }
 
     fn match_empty_rows_with_vertices(row: &[Option<Spring>], slots: &[Spring]) -> Vec<Spring> {
@@ -80,32 +104,72 @@ impl SpringMap {
         row: &'a [Option<Spring>],
     ) -> impl Iterator<Item = Vec<Spring>> + 'a {
         let empty_slots = row.iter().filter(|r| r.is_none()).count();
-        
+
         itertools::repeat_n([Spring::Operational, Spring::Damaged], empty_slots)
             .multi_cartesian_product()
-            //.inspect(|p| println!("p! {:?}", p))
-            .map(move |slots| SpringMap::match_empty_rows_with_vertices(&row, &slots))
+            .inspect(|p| trace!("slots {:?}", p))
+            .map(move |slots| SpringMap::match_empty_rows_with_vertices(row, &slots))
     }
 
-    fn generate_permutation_count_for(&self, index: usize) -> usize {
+    pub fn generate_permutation_count_for(&self, index: usize) -> usize {
         let (spring, counts) = &self.rows[index];
 
         SpringMap::generate_permutation_for(spring)
             .filter(|p| self.verify_if_line_is_valid(p, counts))
-            //.inspect(|p| println!("p {:?} {:?}", p, counts))
+            .inspect(|p| trace!("valid {:?} {:?}", p, counts))
             .count()
     }
 
-    fn generate_permutation_sum(&self) -> usize {
-        (0..self.rows.len()).into_iter().map(|i| self.generate_permutation_count_for(i)).sum()
+    pub fn generate_permutation_sum(&self) -> usize {
+        (0..self.rows.len())
+            .map(|i| self.generate_permutation_count_for(i))
+            .sum()
+    }
+}
+
+#[derive(Default)]
+pub struct Day12;
+
+impl Solver for Day12 {
+    type Input = SpringMap;
+
+    fn day(&self) -> u8 {
+        12
+    }
+
+    fn name(&self) -> &'static str {
+        "Hot Springs"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        SpringMap::new(lines)
+    }
+
+    fn part1(&self, sm: &Self::Input) -> Option<Answer> {
+        debug!("{:?}", sm);
+        Some(sm.generate_permutation_sum().into())
     }
 }
 
-pub fn day12() {
-    //let game_file = read_file_as_text("./inputs/day12test.txt").lines();
-    let game_file = read_file_as_text("./inputs/day12real.txt").lines();
-    let sm = SpringMap::new(game_file.filter_map(|s| s.ok()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
 
-    //println!("Hello {:?}", sm);
-    println!("{}", sm.generate_permutation_sum());
+    const EXAMPLE: &str = include_str!("../inputs/day12test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day12, EXAMPLE, 1), Some(Answer::Int(21)));
+    }
+
+    #[test]
+    fn counts_must_fit_in_the_row() {
+        let row = SpringMap::parse_spring_line;
+
+        assert!(row("???.### 1,1,3").is_ok());
+        assert_eq!(row("???.### 1,2,3").unwrap_err().column, 9);
+        assert_eq!(row("???.### 1,,3").unwrap_err().column, 11);
+        assert_eq!(row("???.### 1,x").unwrap_err().found, "\"x\"");
+    }
 }
@@ -1,118 +1,217 @@
-use crate::util::read_file_as_text;
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{configure_from, Answer, Solver};
+use log::trace;
 use regex::Regex;
+use serde::Deserialize;
 use std::cmp;
 use std::default::Default;
-use std::io::prelude::*;
 
-#[derive(Debug, Clone, Default)]
-struct CubeSet {
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default = "CubeSet::puzzle_bag", deny_unknown_fields)]
+pub struct CubeSet {
     pub blues: usize,
     pub greens: usize,
     pub reds: usize,
 }
 
 impl CubeSet {
+    /// Cubes in the bag of the puzzle, which the colors missing from
+    /// aoc.toml fall back to
+    pub fn puzzle_bag() -> CubeSet {
+        CubeSet {
+            blues: 14,
+            greens: 13,
+            reds: 12,
+        }
+    }
+
     pub fn power(&self) -> usize {
         self.blues * self.greens * self.reds
     }
 }
 
-type GameID = usize;
+pub type GameID = usize;
 
-type Game = (GameID, Vec<CubeSet>);
+pub type Game = (GameID, Vec<CubeSet>);
 
-fn parse_game(line: &str) -> GameID {
+fn parse_game(line: &str, game: &str) -> Result<GameID, ParseError> {
     // Game ???
-    let game_number = &line[4..].trim();
-    game_number.parse::<GameID>().unwrap()
+    let game_number = game
+        .strip_prefix("Game")
+        .ok_or_else(|| ParseError::in_piece(line, game, "\"Game\""))?
+        .trim();
+
+    game_number
+        .parse::<GameID>()
+        .map_err(|_| ParseError::in_piece(line, game_number, "a game id"))
 }
 
-fn parse_each_set(line: &str) -> CubeSet {
+fn parse_each_set(line: &str, set: &str) -> Result<CubeSet, ParseError> {
     // 3 X, 4 Y
     let regex = Regex::new(r"(?m)\s*(\d*)\s*([a-z]*)").unwrap();
 
-    line.split(',').fold(CubeSet::default(), |acc, cube_line| {
-        let caps = regex.captures(cube_line).unwrap();
-        let count = caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-        let color = caps.get(2).unwrap().as_str();
-
-        match color {
-            "blue" => CubeSet {
-                blues: acc.blues + count,
-                ..acc
-            },
-            "green" => CubeSet {
-                greens: acc.greens + count,
-                ..acc
-            },
-            "red" => CubeSet {
-                reds: acc.reds + count,
-                ..acc
-            },
-            _ => panic!("Color unsupported!"),
-        }
-    })
+    set.split(',')
+        .try_fold(CubeSet::default(), |acc, cube_line| {
+            // every group of the regex is optional, so it always matches
+            let caps = regex.captures(cube_line).unwrap();
+            let count = caps.get(1).unwrap().as_str();
+            let count = count
+                .parse::<usize>()
+                .map_err(|_| ParseError::in_piece(line, count, "a cube count"))?;
+            let color = caps.get(2).unwrap().as_str();
+
+            match color {
+                "blue" => Ok(CubeSet {
+                    blues: acc.blues + count,
+                    ..acc
+                }),
+                "green" => Ok(CubeSet {
+                    greens: acc.greens + count,
+                    ..acc
+                }),
+                "red" => Ok(CubeSet {
+                    reds: acc.reds + count,
+                    ..acc
+                }),
+                _ => Err(ParseError::in_piece(
+                    line,
+                    color,
+                    "a color (red, green or blue)",
+                )),
+            }
+        })
 }
 
-fn parse_round(line: &str) -> Vec<CubeSet> {
+fn parse_round(line: &str, round: &str) -> Result<Vec<CubeSet>, ParseError> {
     // 3 X, 4 Y; 1 X, 2 Y...
-    line.split(';').map(parse_each_set).collect()
+    round
+        .split(';')
+        .map(|set| parse_each_set(line, set))
+        .collect()
 }
 
-fn parse_line(line: &str) -> Game {
+pub fn parse_line(line: &str) -> Result<Game, ParseError> {
     let mut game_split = line.split(':');
 
-    let game_id = parse_game(game_split.next().unwrap());
-    let game_sets = parse_round(game_split.next().unwrap());
+    // split always returns at least one piece
+    let game_id = parse_game(line, game_split.next().unwrap())?;
+    let game_sets = match game_split.next() {
+        Some(round) => parse_round(line, round)?,
+        None => return Err(ParseError::at_end(line, "':' after the game id")),
+    };
 
-    (game_id, game_sets)
+    Ok((game_id, game_sets))
 }
 
-pub fn _day2t1() {
-    //let game_file = read_file_as_text("./inputs/day2test1.txt");
-    let game_file = read_file_as_text("./inputs/day2real.txt");
-
-    let parsed_game = game_file
-        .lines()
-        .map(|v| parse_line(&v.unwrap()))
-        .inspect(|e| eprintln!("{:?}", e));
-    let id_sum: usize = parsed_game
-        .filter(|(_game, sets)| {
-            let is_every_round_possible = sets
-                .iter()
-                .all(|set| set.reds <= 12 && set.greens <= 13 && set.blues <= 14);
-            println!("{:?}", is_every_round_possible);
-            is_every_round_possible
-        })
-        .map(|(game, _sets)| game)
-        .inspect(|e| eprintln!("<<{}>> ", e))
-        .sum();
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Day2 {
+    /// Cubes in the bag, for part 1
+    pub bag: CubeSet,
+}
 
-    println!("\n\n{}", id_sum);
+impl Default for Day2 {
+    fn default() -> Self {
+        Day2 {
+            bag: CubeSet::puzzle_bag(),
+        }
+    }
 }
 
-pub fn day2() {
-    //let game_file = read_file_as_text("./inputs/day2test1.txt");
-    let game_file = read_file_as_text("./inputs/day2real.txt");
-
-    let parsed_game = game_file.lines().map(|v| parse_line(&v.unwrap()));
-    let id_sum: usize = parsed_game
-        .map(|(_game, sets)| {
-            let minimum_set = sets
-                .iter()
-                .cloned()
-                .reduce(|acc, round| CubeSet {
-                    blues: cmp::max(acc.blues, round.blues),
-                    greens: cmp::max(acc.greens, round.greens),
-                    reds: cmp::max(acc.reds, round.reds),
-                })
-                .unwrap();
-
-            let power = minimum_set.power();
-            eprintln!("{:?} {}", minimum_set, power);
-            power
-        })
-        .sum();
+impl Solver for Day2 {
+    type Input = Vec<Game>;
+
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "Cube Conundrum"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        parse_each_line(lines, parse_line)
+    }
 
-    println!("\n\n{}", id_sum);
+    fn part1(&self, input: &Self::Input) -> Option<Answer> {
+        let id_sum: usize = input
+            .iter()
+            .inspect(|e| trace!("game {:?}", e))
+            .filter(|(_game, sets)| {
+                let is_every_round_possible = sets.iter().all(|set| {
+                    set.reds <= self.bag.reds
+                        && set.greens <= self.bag.greens
+                        && set.blues <= self.bag.blues
+                });
+                trace!("possible: {:?}", is_every_round_possible);
+                is_every_round_possible
+            })
+            .map(|(game, _sets)| game)
+            .inspect(|e| trace!("possible game {}", e))
+            .sum();
+
+        Some(id_sum.into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> Option<Answer> {
+        let id_sum: usize = input
+            .iter()
+            .map(|(_game, sets)| {
+                let minimum_set = sets
+                    .iter()
+                    .cloned()
+                    .reduce(|acc, round| CubeSet {
+                        blues: cmp::max(acc.blues, round.blues),
+                        greens: cmp::max(acc.greens, round.greens),
+                        reds: cmp::max(acc.reds, round.reds),
+                    })
+                    .unwrap();
+
+                let power = minimum_set.power();
+                trace!("minimum set {:?}, power {}", minimum_set, power);
+                power
+            })
+            .sum();
+
+        Some(id_sum.into())
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> Result<(), String> {
+        configure_from(self, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day2test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(
+            solve_example(&Day2::default(), EXAMPLE, 1),
+            Some(Answer::Int(8))
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            solve_example(&Day2::default(), EXAMPLE, 2),
+            Some(Answer::Int(2286))
+        );
+    }
+
+    #[test]
+    fn colors_missing_from_the_bag_are_the_puzzle_ones() {
+        let mut day = Day2::default();
+        let params = "bag = { reds = 1 }".parse::<toml::Table>().unwrap();
+        day.configure(&params).unwrap();
+
+        assert_eq!((day.bag.blues, day.bag.greens, day.bag.reds), (14, 13, 1));
+        // only game 2 never shows more than one red cube
+        assert_eq!(solve_example(&day, EXAMPLE, 1), Some(Answer::Int(2)));
+    }
 }
@@ -1,49 +1,79 @@
-use crate::util::{generate_adjacencies, read_file_as_text};
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+use crate::util::{Direction, Grid};
+use log::{debug, trace};
 use regex::Regex;
-use std::io::prelude::*;
 
 #[derive(Debug)]
-struct Schematic {
-    data: Vec<Vec<char>>,
+pub struct Schematic {
+    data: Grid<char>,
 }
 
-type SchematicNumber = (usize, usize, usize, u32);
-type GearPosition = (usize, usize);
+pub type SchematicNumber = (usize, usize, usize, u32);
+pub type GearPosition = (usize, usize);
 
 #[allow(dead_code)]
 impl Schematic {
-    fn from_line_iter<S: Iterator<Item = String>>(iter: S) -> Schematic {
-        Schematic {
-            data: iter.map(|c| c.chars().collect()).collect(),
+    /// Lines must be ASCII, so the columns of the numbers are their byte
+    /// offsets, and every number must fit in a `u32`
+    fn check_line(line: &str) -> Result<String, ParseError> {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii()) {
+            return Err(ParseError::in_piece(
+                line,
+                &line[i..i + c.len_utf8()],
+                "an ASCII character",
+            ));
         }
+
+        let too_big = line
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|digits| !digits.is_empty() && digits.parse::<u32>().is_err());
+        if let Some(digits) = too_big {
+            return Err(ParseError::in_piece(
+                line,
+                digits,
+                format!("a number up to {}", u32::MAX),
+            ));
+        }
+
+        Ok(line.to_string())
+    }
+
+    pub fn from_line_iter<S: Iterator<Item = String>>(iter: S) -> Result<Schematic, ParseError> {
+        let lines = parse_each_line(iter, Schematic::check_line)?;
+
+        Ok(Schematic {
+            data: Grid::from_lines(lines.into_iter(), "a character")?,
+        })
     }
 
-    fn retrieve_numbers_and_positions(&self) -> Vec<SchematicNumber> {
+    pub fn retrieve_numbers_and_positions(&self) -> Vec<SchematicNumber> {
         let regex = Regex::new(r"(\d+)").unwrap();
 
         self.data
-            .iter()
+            .rows()
             .enumerate()
-            .map(|(lno, cline)| (lno, cline.iter().clone().collect::<String>()))
+            .map(|(lno, cline)| (lno, cline.iter().collect::<String>()))
             .flat_map(|(lno, line)| {
-                //eprintln!("{:?}", line);
+                trace!("line {}: {:?}", lno, line);
                 regex
                     .find_iter(&line)
+                    // the numbers were checked by `check_line`
                     .map(move |m| (lno, m.start(), m.end(), m.as_str().parse::<u32>().unwrap()))
                     .collect::<Vec<SchematicNumber>>()
             })
             .collect()
     }
 
-    fn retrieve_possible_gears_position(&self) -> Vec<GearPosition> {
+    pub fn retrieve_possible_gears_position(&self) -> Vec<GearPosition> {
         let regex = Regex::new(r"\*").unwrap();
 
         self.data
-            .iter()
+            .rows()
             .enumerate()
-            .map(|(lno, cline)| (lno, cline.iter().clone().collect::<String>()))
+            .map(|(lno, cline)| (lno, cline.iter().collect::<String>()))
             .flat_map(|(lno, line)| {
-                //eprintln!("{:?}", line);
+                trace!("line {}: {:?}", lno, line);
                 regex
                     .find_iter(&line)
                     .map(move |m| (lno, m.start()))
@@ -56,20 +86,24 @@ impl Schematic {
         !value.is_ascii_digit() && value != '.'
     }
 
-    fn generate_adjacencies(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
-        generate_adjacencies(&self.data, x, y)
+    fn generate_adjacencies(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.data
+            .neighbors((y, x), &Direction::ALL)
+            .map(|(_, pos)| pos)
     }
 
     fn is_adjacent_to_symbol(&self, x: usize, y: usize) -> bool {
-        Schematic::generate_adjacencies(self, x, y)
-            .into_iter()
-            .any(|(ay, ax)| {
-                let c = self.data[ay][ax];
-                Schematic::is_symbol(c)
-            })
+        Schematic::generate_adjacencies(self, x, y).any(|(ay, ax)| {
+            let c = self.data[(ay, ax)];
+            Schematic::is_symbol(c)
+        })
     }
 
-    fn retrieve_only_part_numbers(&self, nums: Vec<SchematicNumber>) -> Vec<SchematicNumber> {
+    pub fn retrieve_only_part_numbers(&self, nums: Vec<SchematicNumber>) -> Vec<SchematicNumber> {
         nums.iter()
             .filter(|&&(lineno, xstart, xend, _)| {
                 (xstart..xend).any(|xval| self.is_adjacent_to_symbol(xval, lineno))
@@ -87,14 +121,13 @@ impl Schematic {
         nums.iter()
             .filter(|&&(lineno, xstart, xend, _)| {
                 Schematic::generate_adjacencies(self, gx, gy)
-                    .into_iter()
                     .any(|(ay, ax)| lineno == ay && xstart <= ax && ax < xend)
             })
             .map(|&(_, _, _, number)| number)
             .collect()
     }
 
-    fn retrieve_part_numbers_for_all_gears(
+    pub fn retrieve_part_numbers_for_all_gears(
         &self,
         gears: Vec<GearPosition>,
         nums: Vec<SchematicNumber>,
@@ -112,47 +145,87 @@ impl Schematic {
             .collect()
     }
 
-    fn retrieve_gear_ratio_sum(&self, gears: Vec<(GearPosition, u32, u32)>) -> u64 {
+    pub fn retrieve_gear_ratio_sum(&self, gears: Vec<(GearPosition, u32, u32)>) -> u64 {
         gears.iter().map(|(_, g1, g2)| (g1 * g2) as u64).sum()
     }
 }
 
-pub fn _day3t1() {
-    let game_file = read_file_as_text("./inputs/day3real.txt");
-    //let game_file = read_file_as_text("./inputs/day3test.txt");
-
-    let schematic = Schematic::from_line_iter(game_file.lines().filter_map(|s| s.ok()));
-
-    let numbers = schematic.retrieve_numbers_and_positions();
-    //println!("Hello, {:?}", schematic);
-    //println!("Hello, {:?}", numbers);
-    let part_numbers: Vec<u32> = schematic
-        .retrieve_only_part_numbers(numbers)
-        .into_iter()
-        .map(|(_, _, _, number)| number)
-        .collect();
-    //println!("Hello, {:?}", part_numbers);
-
-    println!(
-        "{}",
-        part_numbers.into_iter().map(|v| v as u64).sum::<u64>()
-    )
+#[derive(Default)]
+pub struct Day3;
+
+impl Solver for Day3 {
+    type Input = Schematic;
+
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn name(&self) -> &'static str {
+        "Gear Ratios"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        Schematic::from_line_iter(lines)
+    }
+
+    fn part1(&self, schematic: &Self::Input) -> Option<Answer> {
+        let numbers = schematic.retrieve_numbers_and_positions();
+        debug!("numbers: {:?}", numbers);
+        let part_numbers: Vec<u32> = schematic
+            .retrieve_only_part_numbers(numbers)
+            .into_iter()
+            .map(|(_, _, _, number)| number)
+            .collect();
+        debug!("part numbers: {:?}", part_numbers);
+
+        Some(
+            part_numbers
+                .into_iter()
+                .map(|v| v as u64)
+                .sum::<u64>()
+                .into(),
+        )
+    }
+
+    fn part2(&self, schematic: &Self::Input) -> Option<Answer> {
+        let numbers = schematic.retrieve_numbers_and_positions();
+        let gears = schematic.retrieve_possible_gears_position();
+        debug!("possible gears: {:?}", gears);
+        let gears = schematic.retrieve_part_numbers_for_all_gears(gears, numbers);
+        debug!("gears: {:?}", gears);
+
+        Some(schematic.retrieve_gear_ratio_sum(gears).into())
+    }
 }
 
-pub fn day3() {
-    let game_file = read_file_as_text("./inputs/day3real.txt");
-    //let game_file = read_file_as_text("./inputs/day3test.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day3test.txt");
 
-    let schematic = Schematic::from_line_iter(game_file.lines().filter_map(|s| s.ok()));
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day3, EXAMPLE, 1), Some(Answer::Int(4361)));
+    }
 
-    let numbers = schematic.retrieve_numbers_and_positions();
-    let gears = schematic.retrieve_possible_gears_position();
-    //println!("Hello, {:?}", gears);
-    let gears = schematic.retrieve_part_numbers_for_all_gears(gears, numbers);
-    //println!("Hello, {:?}", gears);
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day3, EXAMPLE, 2), Some(Answer::Int(467835)));
+    }
 
-    let sum = schematic.retrieve_gear_ratio_sum(gears);
-    println!("{}", sum);
+    #[test]
+    fn big_numbers_and_other_characters_are_errors() {
+        let parse = |text: &str| Schematic::from_line_iter(text.lines().map(String::from));
 
-    //println!("{}", part_numbers.into_iter().map(|v| v as u64).sum::<u64>())
+        let e = parse("467..114..\n..*.99999999999").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 5));
+        assert_eq!(e.found, "\"99999999999\"");
+
+        let e = parse("467..114..\n.é.35..633.").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (2, 2, "\"é\""));
+
+        assert!(parse("4294967295*1").is_ok());
+    }
 }
@@ -1,23 +1,32 @@
 #![allow(dead_code, unused_variables)]
 
-use crate::util::read_file_as_text;
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+use crate::util::parse_numbers;
+use log::{debug, trace};
 use std::collections::HashMap;
-use std::io::prelude::*;
 
 #[derive(Debug)]
-struct Card {
+pub struct Card {
     pub number: usize,
     winning: Vec<u32>,
     numbers: Vec<u32>,
 }
 
 impl Card {
-    fn get_card_number(line: &str) -> usize {
+    fn get_card_number(line: &str, card: &str) -> Result<usize, ParseError> {
         // Card<SPACE><NUMBER>
-        line[4..].trim().parse().unwrap()
+        let number = card
+            .strip_prefix("Card")
+            .ok_or_else(|| ParseError::in_piece(line, card, "\"Card\""))?
+            .trim();
+
+        number
+            .parse()
+            .map_err(|_| ParseError::in_piece(line, number, "a card number"))
     }
 
-    fn get_won_number_count(&self) -> usize {
+    pub fn get_won_number_count(&self) -> usize {
         self.numbers
             .iter()
             .filter(|n| self.winning.contains(n))
@@ -31,7 +40,7 @@ impl Card {
             .collect()
     }
 
-    fn get_point_count(won_numbers: usize) -> usize {
+    pub fn get_point_count(won_numbers: usize) -> usize {
         let base: usize = 2;
         match won_numbers {
             0 => 0,
@@ -39,47 +48,36 @@ impl Card {
         }
     }
 
-    fn parse_numbers(line: &str) -> Vec<u32> {
-        line.split(' ')
-            .filter_map(|num| num.trim().parse().ok())
-            .collect()
-    }
-
-    fn get_winning_and_received_numbers(line: &str) -> (Vec<u32>, Vec<u32>) {
-        let mut parts = line.split('|');
-        let winning = Card::parse_numbers(parts.next().unwrap());
-        let received = Card::parse_numbers(parts.next().unwrap());
-
-        (winning, received)
+    fn get_winning_and_received_numbers(
+        line: &str,
+        numbers: &str,
+    ) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+        let mut parts = numbers.split('|');
+        let winning = parse_numbers(line, parts.next().unwrap(), ' ', "a winning number")?;
+        let received = match parts.next() {
+            Some(received) => parse_numbers(line, received, ' ', "a number")?,
+            None => return Err(ParseError::at_end(line, "'|' after the winning numbers")),
+        };
+
+        Ok((winning, received))
     }
 
-    fn from_line(line: &str) -> Self {
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
         let mut parts = line.split(':');
-        let number = Card::get_card_number(parts.next().unwrap());
-        let (winning, numbers) = Card::get_winning_and_received_numbers(parts.next().unwrap());
+        let number = Card::get_card_number(line, parts.next().unwrap())?;
+        let (winning, numbers) = match parts.next() {
+            Some(numbers) => Card::get_winning_and_received_numbers(line, numbers)?,
+            None => return Err(ParseError::at_end(line, "':' after the card number")),
+        };
 
-        Card {
+        Ok(Card {
             number,
             winning,
             numbers,
-        }
+        })
     }
 }
 
-pub fn _day4t1() {
-    let game_file = read_file_as_text("./inputs/day4real.txt");
-    //let game_file = read_file_as_text("./inputs/day4test.txt");
-    let card_total: usize = game_file
-        .lines()
-        .map(|line| Card::from_line(&line.unwrap()))
-        .inspect(|c| print!("Cards: {:?}", c))
-        .map(|c| Card::get_point_count(c.get_won_number_count()))
-        .inspect(|c| println!(" count {}", c))
-        .sum();
-
-    println!("{}", card_total);
-}
-
 fn process_card_retrieve_won_cards(
     total_cards: usize,
     cards_received: usize,
@@ -94,7 +92,7 @@ fn process_card_retrieve_won_cards(
         .collect()
 }
 
-fn process_all_cards(cards: &[Card]) -> HashMap<usize, usize> {
+pub fn process_all_cards(cards: &[Card]) -> HashMap<usize, usize> {
     let mut count_list: HashMap<usize, usize> = cards.iter().map(|c| (c.number, 1)).collect();
 
     for c in cards {
@@ -109,16 +107,67 @@ fn process_all_cards(cards: &[Card]) -> HashMap<usize, usize> {
     count_list
 }
 
-pub fn day4() {
-    let game_file = read_file_as_text("./inputs/day4real.txt");
-    //let game_file = read_file_as_text("./inputs/day4test.txt");
-    let cards: Vec<Card> = game_file
-        .lines()
-        .map(|line| Card::from_line(&line.unwrap()))
-        .collect();
-    let card_counts = process_all_cards(&cards);
-    let card_sum: usize = card_counts.values().sum();
-
-    println!("{:?}", card_counts);
-    println!("{}", card_sum);
+#[derive(Default)]
+pub struct Day4;
+
+impl Solver for Day4 {
+    type Input = Vec<Card>;
+
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn name(&self) -> &'static str {
+        "Scratchcards"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        parse_each_line(lines, Card::from_line)
+    }
+
+    fn part1(&self, cards: &Self::Input) -> Option<Answer> {
+        let card_total: usize = cards
+            .iter()
+            .inspect(|c| trace!("card {:?}", c))
+            .map(|c| Card::get_point_count(c.get_won_number_count()))
+            .inspect(|c| trace!("points {}", c))
+            .sum();
+
+        Some(card_total.into())
+    }
+
+    fn part2(&self, cards: &Self::Input) -> Option<Answer> {
+        let card_counts = process_all_cards(cards);
+        let card_sum: usize = card_counts.values().sum();
+
+        debug!("card counts: {:?}", card_counts);
+        Some(card_sum.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day4test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day4, EXAMPLE, 1), Some(Answer::Int(13)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day4, EXAMPLE, 2), Some(Answer::Int(30)));
+    }
+
+    #[test]
+    fn numbers_that_do_not_parse_are_errors() {
+        let e = Card::from_line("Card 1: 41 4B 83 | 83 86").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (12, "\"4B\""));
+
+        let e = Card::from_line("Card 1: 41 48 | 83 -").unwrap_err();
+        assert_eq!(e.column, 20);
+    }
 }
@@ -1,28 +1,35 @@
 #![allow(dead_code, unused_mut, unused_variables)]
 
-use crate::util::{read_file_as_text, split_numbers_by_space};
-use std::io::prelude::*;
-
-type SeedRange = (usize, usize);
-
-#[derive(Debug, Clone)]
-struct AlmanacRange {
-    destination_start: usize,
-    source_start: usize,
-    range_length: usize,
+use crate::error::ParseError;
+use crate::solver::{Answer, Solver, Strategy};
+use crate::util::labelled_numbers;
+use log::{debug, error, trace};
+
+pub type SeedRange = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlmanacRange {
+    pub destination_start: usize,
+    pub source_start: usize,
+    pub range_length: usize,
 }
 
 impl AlmanacRange {
-    fn from_line(l: &str) -> AlmanacRange {
+    pub fn from_line(l: &str) -> Result<AlmanacRange, ParseError> {
         let mut elements = l.split(' ');
-        AlmanacRange {
-            destination_start: elements.next().unwrap().parse().unwrap(),
-            source_start: elements.next().unwrap().parse().unwrap(),
-            range_length: elements.next().unwrap().parse().unwrap(),
-        }
+        let mut next_number = |what: &str| match elements.next() {
+            Some(e) => e.parse().map_err(|_| ParseError::in_piece(l, e, what)),
+            None => Err(ParseError::at_end(l, what)),
+        };
+
+        Ok(AlmanacRange {
+            destination_start: next_number("the destination start")?,
+            source_start: next_number("the source start")?,
+            range_length: next_number("the range length")?,
+        })
     }
 
-    fn correspondences(ranges: &[AlmanacRange], source: usize) -> usize {
+    pub fn correspondences(ranges: &[AlmanacRange], source: usize) -> usize {
         ranges
             .iter()
             .find(|r| {
@@ -36,94 +43,69 @@ impl AlmanacRange {
             .unwrap_or(source)
     }
 
-    fn correspondence_ranges(ranges: &[AlmanacRange], source: SeedRange) -> Vec<SeedRange> {
+    /// Map a whole range of sources. The pieces of the range that fall in
+    /// different ranges of the map, or in none, become separate ranges.
+    pub fn correspondence_ranges(ranges: &[AlmanacRange], source: SeedRange) -> Vec<SeedRange> {
+        let (mut current, length) = source;
+        let end = current + length;
+
         let mut ranges_in_source: Vec<&AlmanacRange> = ranges
             .iter()
-            .filter(|r| {
-                let (sstart, slen) = source;
-                let range_end = r.source_start + r.range_length;
-
-                (r.source_start.saturating_sub(r.range_length)) <= sstart
-                    && (sstart + slen) <= (range_end)
-            })
+            .filter(|r| r.source_start < end && current < r.source_start + r.range_length)
             .collect();
+        ranges_in_source.sort_by_key(|r| r.source_start);
+
+        let mut ret = vec![];
+        for range in ranges_in_source {
+            // the numbers before the range map to themselves
+            if current < range.source_start {
+                ret.push((current, range.source_start - current));
+                current = range.source_start;
+            }
+
+            let range_end = (range.source_start + range.range_length).min(end);
+            if range_end <= current {
+                continue;
+            }
+
+            let offset = current - range.source_start;
+            ret.push((range.destination_start + offset, range_end - current));
+            current = range_end;
+        }
 
-        if ranges_in_source.is_empty() {
-            vec![source]
-        } else {
-            vec![source]
-            /*ranges_in_source.sort_by(|a, b| a.source_start.cmp(&b.source_start));
-              //println!("\n\n\n\n\n\n\nris {:?} {:?}", ranges_in_source, source);
-
-              let mut ret = vec![];
-              let mut current = source.0;
-              let end = current + source.1;
-
-              eprintln!("-");
-              for range in ranges_in_source {
-                  if current < range.source_start {
-                      let len = (range.source_start - current).min(end-current);
-                      ret.push((current, len));
-                      current += len;
-                  }
-
-                  if current >= end {
-                      //eprintln!("{} ret {:?}", current, ret);
-                      break;
-                  }
-
-                  let from_start = range.source_start;
-                  let to_start = range.destination_start;
-                  let from_end = from_start + range.range_length;
-                  let offset = current - range.source_start;
-                  let real_start = from_start + offset;
-
-                  //eprintln!("{} - {}", end, current);
-                  let remaining_len = end - current;
-
-                  let length = (remaining_len).min(from_end - real_start);
-                  //println!("{} = min({}, {} - {}) {}", length, remaining_len,
-                  //from_end, real_start, from_end-real_start);
-
-                  //                println!("fe {} {:?} {}, {}, {}", length, ((to_start+offset, length)), end, current,
-                  //         end.saturating_sub(current));
-
-
-                  //eprintln!("r {:?}", range);
-                  ret.push((to_start + offset, length));
-                  //eprintln!("{} ret {:?}", current, ret);
-
-                  current += length;
-
-                  if current >= end {
-                      break;
-                  }
-              }
-
-              assert!(current <= end);
-              if current < end {
-                  ret.push((current, end - current));
-              }
-
-              ret
-            */
+        if current < end {
+            ret.push((current, end - current));
         }
+
+        ret
     }
 }
 
 #[derive(Debug)]
-struct SeedFile {
-    initial_seeds: Vec<SeedRange>,
-    //initial_seeds: Vec<usize>, p1
-    seed_to_soil: Vec<AlmanacRange>,
-    soil_to_fertilizer: Vec<AlmanacRange>,
-    fertilizer_to_water: Vec<AlmanacRange>,
-    water_to_light: Vec<AlmanacRange>,
-    light_to_temperature: Vec<AlmanacRange>,
-    temperature_to_humidity: Vec<AlmanacRange>,
-    humidity_to_location: Vec<AlmanacRange>,
+pub struct SeedFile {
+    /// The numbers of the seed line: seeds for part 1, and pairs of start
+    /// and length for part 2
+    pub seeds: Vec<usize>,
+    pub seed_to_soil: Vec<AlmanacRange>,
+    pub soil_to_fertilizer: Vec<AlmanacRange>,
+    pub fertilizer_to_water: Vec<AlmanacRange>,
+    pub water_to_light: Vec<AlmanacRange>,
+    pub light_to_temperature: Vec<AlmanacRange>,
+    pub temperature_to_humidity: Vec<AlmanacRange>,
+    pub humidity_to_location: Vec<AlmanacRange>,
 }
 
+/// Headers of the maps, in the order they are used
+const MAP_HEADERS: [&str; 7] = [
+    "seed-to-soil map:",
+    "soil-to-fertilizer map:",
+    "fertilizer-to-water map:",
+    "water-to-light map:",
+    "light-to-temperature map:",
+    "temperature-to-humidity map:",
+    "humidity-to-location map:",
+];
+
 enum SeedFileParseState {
     NoState,
     SeedToSoil,
@@ -136,15 +118,20 @@ enum SeedFileParseState {
 }
 
 impl SeedFile {
-    /* fn initial_seeds(&self) -> &[usize] {
-        &self.initial_seeds
-    } */
+    pub fn seeds(&self) -> &[usize] {
+        &self.seeds
+    }
+
+    /// The seeds as ranges, or `None` if there is a start without a length
+    pub fn seed_ranges(&self) -> Option<Vec<SeedRange>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return None;
+        }
 
-    fn initial_seeds(&self) -> &[(usize, usize)] {
-        &self.initial_seeds
+        Some(self.seeds.chunks(2).map(|v| (v[0], v[1])).collect())
     }
 
-    fn seed_to_location(&self, seed: usize) -> usize {
+    pub fn seed_to_location(&self, seed: usize) -> usize {
         let soil = AlmanacRange::correspondences(&self.seed_to_soil, seed);
         let fertilizer = AlmanacRange::correspondences(&self.soil_to_fertilizer, soil);
         let water = AlmanacRange::correspondences(&self.fertilizer_to_water, fertilizer);
@@ -152,15 +139,21 @@ impl SeedFile {
         let temperature = AlmanacRange::correspondences(&self.light_to_temperature, light);
         let humidity = AlmanacRange::correspondences(&self.temperature_to_humidity, temperature);
 
-        /* eprintln!(
-            "soil {} fert {} water {} light {} temp {} humidity {}",
-            soil, fertilizer, water, light, temperature, humidity
-        ); */
+        trace!(
+            "seed {} soil {} fert {} water {} light {} temp {} humidity {}",
+            seed,
+            soil,
+            fertilizer,
+            water,
+            light,
+            temperature,
+            humidity
+        );
 
         AlmanacRange::correspondences(&self.humidity_to_location, humidity)
     }
 
-    fn seed_to_location_ranges(&self, seed: SeedRange) -> Vec<SeedRange> {
+    pub fn seed_to_location_ranges(&self, seed: SeedRange) -> Vec<SeedRange> {
         let soil = AlmanacRange::correspondence_ranges(&self.seed_to_soil, seed);
         let fertilizer: Vec<SeedRange> = soil
             .iter()
@@ -187,7 +180,7 @@ impl SeedFile {
             .flat_map(|s| AlmanacRange::correspondence_ranges(&self.humidity_to_location, *s))
             .collect();
 
-        eprintln!(
+        debug!(
             "\tseed: {:?}\n\tsoil {:?} \n\tfert {:?} \n\twater {:?} \n\tlight {:?} \n\ttemp {:?} \n\thumidity {:?}\n\tlocation: {:?}",
             seed, soil, fertilizer, water, light, temperature, humidity, location
         );
@@ -195,58 +188,72 @@ impl SeedFile {
         location
     }
 
+    /// Lowest location of the seed ranges, mapping whole ranges at once.
+    /// `None` when the seeds are not pairs, or there are none.
+    pub fn lowest_location_from_ranges(&self) -> Option<usize> {
+        self.seed_ranges()?
+            .into_iter()
+            .flat_map(|s| self.seed_to_location_ranges(s))
+            .inspect(|s| trace!("location range {:?}", s))
+            .map(|(rs, _)| rs)
+            .min()
+    }
+
+    /// Same as `lowest_location_from_ranges`, but mapping each seed, which
+    /// takes minutes on the real input
+    pub fn lowest_location_seed_by_seed(&self) -> Option<usize> {
+        self.seed_ranges()?
+            .into_iter()
+            .flat_map(|(ss, sl)| ss..(ss + sl))
+            .map(|s| self.seed_to_location(s))
+            .min()
+    }
+
     fn finish_state(
         initial_seed: SeedFile,
-        current_vec: &Vec<AlmanacRange>,
+        current_vec: &[AlmanacRange],
         current_state: SeedFileParseState,
     ) -> SeedFile {
         match current_state {
             SeedFileParseState::NoState => initial_seed,
             SeedFileParseState::SeedToSoil => SeedFile {
-                seed_to_soil: current_vec.clone(),
+                seed_to_soil: current_vec.to_vec(),
                 ..initial_seed
             },
             SeedFileParseState::SoilToFertilizer => SeedFile {
-                soil_to_fertilizer: current_vec.clone(),
+                soil_to_fertilizer: current_vec.to_vec(),
                 ..initial_seed
             },
             SeedFileParseState::FertilizerToWater => SeedFile {
-                fertilizer_to_water: current_vec.clone(),
+                fertilizer_to_water: current_vec.to_vec(),
                 ..initial_seed
             },
             SeedFileParseState::WaterToLight => SeedFile {
-                water_to_light: current_vec.clone(),
+                water_to_light: current_vec.to_vec(),
                 ..initial_seed
             },
             SeedFileParseState::LightToTemperature => SeedFile {
-                light_to_temperature: current_vec.clone(),
+                light_to_temperature: current_vec.to_vec(),
                 ..initial_seed
             },
             SeedFileParseState::TemperatureToHumidity => SeedFile {
-                temperature_to_humidity: current_vec.clone(),
+                temperature_to_humidity: current_vec.to_vec(),
                 ..initial_seed
             },
             SeedFileParseState::HumidityToLocation => SeedFile {
-                humidity_to_location: current_vec.clone(),
+                humidity_to_location: current_vec.to_vec(),
                 ..initial_seed
             },
         }
     }
 
-    fn split_initial_seeds(line: &str) -> Vec<(usize, usize)> {
-        split_numbers_by_space(line)
-            .chunks(2)
-            .map(|v| (v[0], v[1]))
-            .collect()
-    }
-
-    fn from_lines(mut lines: impl Iterator<Item = String>) -> SeedFile {
-        let seed_line = lines.next().unwrap();
-        let initial_seeds = SeedFile::split_initial_seeds(&seed_line[6..]);
-        //let initial_seeds = split_numbers_by_space(&seed_line[6..]);
+    pub fn from_lines(mut lines: impl Iterator<Item = String>) -> Result<SeedFile, ParseError> {
+        let seed_line = lines.next().unwrap_or_default();
+        let seeds =
+            labelled_numbers(&seed_line, "seeds", "a seed number").map_err(|e| e.at_line(1))?;
 
         let mut initial_seed = SeedFile {
-            initial_seeds,
+            seeds,
             seed_to_soil: vec![],
             soil_to_fertilizer: vec![],
             fertilizer_to_water: vec![],
@@ -258,7 +265,27 @@ impl SeedFile {
 
         let mut current_state: SeedFileParseState = SeedFileParseState::NoState;
         let mut current_vec: Vec<AlmanacRange> = vec![];
-        for line in lines {
+        let mut seen_headers = vec![];
+        let mut line_count = 1;
+        for (index, line) in lines.enumerate() {
+            // the seeds are in the first line
+            let lineno = index + 2;
+            line_count = lineno;
+
+            if MAP_HEADERS.contains(&line.as_str()) {
+                if seen_headers.contains(&line) {
+                    return Err(
+                        ParseError::in_piece(&line, &line, "each map only once").at_line(lineno)
+                    );
+                }
+                seen_headers.push(line.clone());
+
+                // the map before may not end with an empty line
+                initial_seed = SeedFile::finish_state(initial_seed, &current_vec, current_state);
+                current_state = SeedFileParseState::NoState;
+                current_vec.clear();
+            }
+
             match line.as_str() {
                 "seed-to-soil map:" => {
                     current_state = SeedFileParseState::SeedToSoil;
@@ -289,77 +316,197 @@ impl SeedFile {
                     current_vec.clear();
                 }
                 l if !l.contains("map:") => {
+                    if let SeedFileParseState::NoState = current_state {
+                        return Err(ParseError::in_piece(l, l, "a map header").at_line(lineno));
+                    }
+
                     // vector list
-                    current_vec.push(AlmanacRange::from_line(&line))
+                    let range = AlmanacRange::from_line(l).map_err(|e| e.at_line(lineno))?;
+                    current_vec.push(range)
+                }
+                l => {
+                    return Err(ParseError::in_piece(
+                        l,
+                        l,
+                        "a known map, like \"seed-to-soil map:\"",
+                    )
+                    .at_line(lineno))
                 }
-                _ => {}
             }
         }
 
-        SeedFile::finish_state(initial_seed, &current_vec, current_state)
+        // a missing map would leave the seeds where they are
+        if let Some(missing) = MAP_HEADERS
+            .iter()
+            .find(|h| !seen_headers.iter().any(|s| s == *h))
+        {
+            return Err(ParseError::at_end_of_input(
+                line_count,
+                format!("the {:?} section", missing),
+            ));
+        }
+
+        Ok(SeedFile::finish_state(
+            initial_seed,
+            &current_vec,
+            current_state,
+        ))
     }
 }
 
-/*
-pub fn day5() {
-    //let game_file = read_file_as_text("./inputs/day5real.txt").lines();
-    let game_file = read_file_as_text("./inputs/day5test.txt").lines();
-    let seed_file = SeedFile::from_lines(game_file.map(|l| l.unwrap()));
+#[derive(Default)]
+pub struct Day5;
 
-    //let ranges = vec![AlmanacRange::from_line("50 98 2"), AlmanacRange::from_line("52 50 48")];
-    //println!("Hello {:?} ", seed_file);
+impl Solver for Day5 {
+    type Input = SeedFile;
 
-    let min_location = seed_file
-        .initial_seeds()
-        .iter()
-        .map(|s| seed_file.seed_to_location(*s))
-        .inspect(|s| eprintln!("seed {}", s))
-        .min()
-        .unwrap();
+    fn day(&self) -> u8 {
+        5
+    }
 
-println!("\n{}", min_location);
-}
- */
-// 198106515: too high
-// 26714516: certa
-
-/*
-pub fn day5() {
-    //let game_file = read_file_as_text("./inputs/day5real.txt").lines();
-    let game_file = read_file_as_text("./inputs/day5real.txt").lines();
-    let seed_file = SeedFile::from_lines(game_file.map(|l| l.unwrap()));
-
-    //let ranges = vec![AlmanacRange::from_line("50 98 2"), AlmanacRange::from_line("52 50 48")];
-    //println!("Hello {:?} ", seed_file);
-
-    let min_location = seed_file
-        .initial_seeds()
-        .iter()
-        .flat_map(|&(ss,sl)| ss..(ss+sl))
-        .map(|s| seed_file.seed_to_location(s))
-        //.inspect(|s| eprintln!("seed {}", s))
-        .min()
-        .unwrap();
-
-    println!("\n{}", min_location);
+    fn name(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        SeedFile::from_lines(lines)
+    }
+
+    fn part1(&self, seed_file: &Self::Input) -> Option<Answer> {
+        debug!("{:?}", seed_file);
+
+        let min_location = seed_file
+            .seeds()
+            .iter()
+            .map(|&s| seed_file.seed_to_location(s))
+            .inspect(|s| trace!("location {}", s))
+            .min()?;
+
+        Some(min_location.into())
+    }
+
+    fn part2(&self, seed_file: &Self::Input) -> Option<Answer> {
+        debug!("{:?}", seed_file);
+        if seed_file.seed_ranges().is_none() {
+            error!("the seeds are not pairs of a start and a length");
+        }
+
+        Some(seed_file.lowest_location_from_ranges()?.into())
+    }
+
+    fn strategies(&self, part: u8) -> Vec<Strategy<SeedFile>> {
+        match part {
+            2 => vec![("brute-force", |seed_file| {
+                Some(seed_file.lowest_location_seed_by_seed()?.into())
+            })],
+            _ => vec![],
+        }
+    }
 }
-*/
-
-pub fn day5() {
-    let game_file = read_file_as_text("./inputs/day5real.txt").lines();
-    //let game_file = read_file_as_text("./inputs/day5test.txt").lines();
-    let seed_file = SeedFile::from_lines(game_file.map(|l| l.unwrap()));
-
-    //let ranges = vec![AlmanacRange::from_line("50 98 2"), AlmanacRange::from_line("52 50 48")];
-    println!("Hello {:?} ", seed_file);
-    let locations = seed_file
-        .initial_seeds() // &[(3082872446, 316680412)]
-        .iter()
-        .flat_map(|s| seed_file.seed_to_location_ranges(*s))
-        //.inspect(|s| eprintln!("ranges {:?}", s))
-        .map(|(rs, _)| rs)
-        .min()
-        .unwrap();
-
-    println!("\n{}", locations)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day5test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day5, EXAMPLE, 1), Some(Answer::Int(35)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day5, EXAMPLE, 2), Some(Answer::Int(46)));
+    }
+
+    #[test]
+    fn correspondences_map_inside_ranges_only() {
+        let ranges = vec![
+            AlmanacRange::from_line("50 98 2").unwrap(),
+            AlmanacRange::from_line("52 50 48").unwrap(),
+        ];
+
+        assert_eq!(AlmanacRange::correspondences(&ranges, 79), 81);
+        assert_eq!(AlmanacRange::correspondences(&ranges, 98), 50);
+        assert_eq!(AlmanacRange::correspondences(&ranges, 99), 51);
+        assert_eq!(AlmanacRange::correspondences(&ranges, 14), 14);
+        assert_eq!(AlmanacRange::correspondences(&ranges, 100), 100);
+    }
+
+    #[test]
+    fn correspondence_ranges_are_split() {
+        let ranges = vec![
+            AlmanacRange::from_line("50 98 2").unwrap(),
+            AlmanacRange::from_line("52 50 48").unwrap(),
+        ];
+
+        assert_eq!(
+            AlmanacRange::correspondence_ranges(&ranges, (45, 60)),
+            vec![(45, 5), (52, 48), (50, 2), (100, 5)]
+        );
+        assert_eq!(
+            AlmanacRange::correspondence_ranges(&ranges, (60, 10)),
+            vec![(62, 10)]
+        );
+        assert_eq!(
+            AlmanacRange::correspondence_ranges(&ranges, (0, 10)),
+            vec![(0, 10)]
+        );
+    }
+
+    #[test]
+    fn an_odd_number_of_seeds_is_only_valid_for_part_1() {
+        let example = EXAMPLE.replacen("seeds: 79 14 55 13", "seeds: 79 14 55", 1);
+
+        assert_eq!(solve_example(&Day5, &example, 1), Some(Answer::Int(43)));
+        assert_eq!(solve_example(&Day5, &example, 2), None);
+    }
+
+    #[test]
+    fn without_seeds_there_is_no_answer() {
+        let example = EXAMPLE.replacen("seeds: 79 14 55 13", "seeds:", 1);
+
+        assert_eq!(solve_example(&Day5, &example, 1), None);
+        assert_eq!(solve_example(&Day5, &example, 2), None);
+    }
+
+    #[test]
+    fn part2_seed_by_seed() {
+        let seed_file = Day5.parse(EXAMPLE.lines().map(String::from)).unwrap();
+        assert_eq!(seed_file.lowest_location_seed_by_seed(), Some(46));
+    }
+
+    #[test]
+    fn every_map_is_needed_once() {
+        let lines = || EXAMPLE.lines().map(String::from);
+
+        // without the water-to-light section, in lines 18 to 21
+        let missing = lines()
+            .enumerate()
+            .filter(|(i, _)| !(17..21).contains(i))
+            .map(|(_, l)| l);
+        let e = SeedFile::from_lines(missing).unwrap_err();
+        assert_eq!((e.line, e.found.as_str()), (30, "end of input"));
+        assert!(e.expected.contains("water-to-light map:"));
+
+        let twice = SeedFile::from_lines(lines().map(|l| match l.as_str() {
+            "light-to-temperature map:" => String::from("seed-to-soil map:"),
+            _ => l,
+        }));
+        assert_eq!(twice.unwrap_err().line, 22);
+    }
+
+    #[test]
+    fn maps_without_an_empty_line_after_them_are_kept_apart() {
+        // every map header right after the ranges of the last map
+        let example = EXAMPLE.replace("\n\n", "\n").replacen("\n", "\n\n", 1);
+        let parse = |text: &str| SeedFile::from_lines(text.lines().map(String::from)).unwrap();
+
+        let (joined, apart) = (parse(&example), parse(EXAMPLE));
+        assert_eq!(joined.seed_to_soil, apart.seed_to_soil);
+        assert_eq!(joined.humidity_to_location, apart.humidity_to_location);
+        assert_eq!(solve_example(&Day5, &example, 1), Some(Answer::Int(35)));
+    }
 }
@@ -1,28 +1,51 @@
 #![allow(dead_code, unused_mut)]
 
-use crate::util::{read_file_as_text, split_numbers_by_space};
-use std::io::prelude::*;
+use crate::error::ParseError;
+use crate::solver::{Answer, Solver, Strategy};
+use crate::util::labelled_numbers;
+use log::{debug, trace};
 
-type Milisecond = usize;
-type Milimeter = usize;
+pub type Milisecond = usize;
+pub type Milimeter = usize;
 
-type Race = (Milisecond, Milimeter); // Race(time_in_ms, record_in_milimeters)
+pub type Race = (Milisecond, Milimeter); // Race(time_in_ms, record_in_milimeters)
 
-fn parse_races(time_line: &str, distance_line: &str) -> Vec<Race> {
-    let times = split_numbers_by_space(&time_line[9..]).into_iter();
-    let distances = split_numbers_by_space(&distance_line[9..]).into_iter();
+pub fn parse_races(times: &[usize], distances: &[usize]) -> Vec<Race> {
+    times
+        .iter()
+        .copied()
+        .zip(distances.iter().copied())
+        .collect()
+}
+
+/// The numbers of a line as a single one, ignoring the spaces between them
+fn join_digits(line: &str, numbers: &[usize]) -> Result<usize, ParseError> {
+    if numbers.is_empty() {
+        return Err(ParseError::at_end(line, "at least one number"));
+    }
+
+    let digits: String = numbers.iter().map(|n| n.to_string()).collect();
 
-    times.zip(distances).map(|(t, d)| (t, d)).collect()
+    digits.parse().map_err(|_| {
+        let numbers = &line[line.find(':').map_or(0, |colon| colon + 1)..];
+        ParseError::in_piece(line, numbers, "numbers that fit in one when joined")
+    })
 }
 
-fn parse_races2(time_line: &str, distance_line: &str) -> Vec<Race> {
-    let times = split_numbers_by_space(&time_line[9..].replace(' ', "")).into_iter();
-    let distances = split_numbers_by_space(&distance_line[9..].replace(' ', "")).into_iter();
+pub fn parse_races2(
+    (time_line, times): (&str, &[usize]),
+    (distance_line, distances): (&str, &[usize]),
+) -> Result<Vec<Race>, ParseError> {
+    let time = join_digits(time_line, times).map_err(|e| e.at_line(1))?;
+    let distance = join_digits(distance_line, distances).map_err(|e| e.at_line(2))?;
 
-    times.zip(distances).map(|(t, d)| (t, d)).collect()
+    Ok(vec![(time, distance)])
 }
 
-fn distance_for_button_held(race_time: Milisecond, button_held_for_ms: Milisecond) -> Milimeter {
+pub fn distance_for_button_held(
+    race_time: Milisecond,
+    button_held_for_ms: Milisecond,
+) -> Milimeter {
     let remaining = race_time.saturating_sub(button_held_for_ms);
 
     // 1 ms held = 1 mm
@@ -30,7 +53,7 @@ fn distance_for_button_held(race_time: Milisecond, button_held_for_ms: Milisecon
     remaining * button_held_for_ms
 }
 
-fn victories_for_each_record(race: Race) -> Vec<Milimeter> {
+pub fn victories_for_each_record(race: Race) -> Vec<Milimeter> {
     let (time, record_distance) = race;
 
     (0..=time)
@@ -39,44 +62,163 @@ fn victories_for_each_record(race: Race) -> Vec<Milimeter> {
         .collect()
 }
 
-/*
-pub fn day6p1() {
-    let game_file = read_file_as_text("./inputs/day6real.txt");
-    //let game_file = read_file_as_text("./inputs/day6test.txt");
+/// How many ways there are to beat the record, without trying each one: the
+/// distance is `held * (time - held)`, so the ways to win are between the
+/// roots of `held^2 - time * held + record`.
+pub fn ways_to_win(race: Race) -> usize {
+    let (time, record_distance) = race;
+
+    // the longest distance is when holding the button for half of the time
+    if distance_for_button_held(time, time / 2) <= record_distance {
+        return 0;
+    }
 
-    let mut lines = game_file.lines().take(2);
-    let races = parse_races(&lines.next().unwrap().unwrap(),
-                            &lines.next().unwrap().unwrap());
+    let discriminant = (time as f64).powi(2) - 4.0 * record_distance as f64;
+    let mut first = ((time as f64 - discriminant.max(0.0).sqrt()) / 2.0) as usize;
 
-    println!("Hello {:?}", races);
-    let victories: usize = races.iter().map(|r| {
-        let vs = victories_for_each_record(r.clone());
-        vs.len()
-    }).inspect(|v| println!("{}", v)).product();
+    // the floating point root can be off by one either way
+    while first > 0 && distance_for_button_held(time, first - 1) > record_distance {
+        first -= 1;
+    }
+    while distance_for_button_held(time, first) <= record_distance {
+        first += 1;
+    }
 
-    println!("\n{}", victories);
+    // the ways to win are symmetrical
+    time - 2 * first + 1
 }
-*/
 
-pub fn day6() {
-    let game_file = read_file_as_text("./inputs/day6real.txt");
-    //let game_file = read_file_as_text("./inputs/day6test.txt");
+fn product_of_ways_to_win(races: &[Race]) -> Option<Answer> {
+    Some(
+        races
+            .iter()
+            .map(|r| ways_to_win(*r))
+            .product::<usize>()
+            .into(),
+    )
+}
 
-    let mut lines = game_file.lines().take(2);
-    let races = parse_races2(
-        &lines.next().unwrap().unwrap(),
-        &lines.next().unwrap().unwrap(),
-    );
+#[derive(Default)]
+pub struct Day6;
+
+impl Solver for Day6 {
+    /// (races for part 1, the single race for part 2)
+    type Input = (Vec<Race>, Vec<Race>);
+
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn name(&self) -> &'static str {
+        "Wait For It"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        let mut lines = lines.take(2);
+        let time_line = lines.next().unwrap_or_default();
+        let distance_line = lines.next().unwrap_or_default();
+
+        let times: Vec<usize> =
+            labelled_numbers(&time_line, "Time", "a race time").map_err(|e| e.at_line(1))?;
+        let distances: Vec<usize> =
+            labelled_numbers(&distance_line, "Distance", "a record distance")
+                .map_err(|e| e.at_line(2))?;
+
+        let expected = format!("{} distances", times.len());
+        if distances.len() < times.len() {
+            return Err(ParseError::at_end(&distance_line, expected).at_line(2));
+        }
+        if distances.len() > times.len() {
+            return Err(ParseError::in_piece(&distance_line, &distance_line, expected).at_line(2));
+        }
+
+        Ok((
+            parse_races(&times, &distances),
+            parse_races2((&time_line, &times), (&distance_line, &distances))?,
+        ))
+    }
+
+    fn part1(&self, (races, _): &Self::Input) -> Option<Answer> {
+        debug!("races: {:?}", races);
+        let victories: usize = races
+            .iter()
+            .map(|r| {
+                let vs = victories_for_each_record(*r);
+                vs.len()
+            })
+            .inspect(|v| trace!("ways to win: {}", v))
+            .product();
+
+        Some(victories.into())
+    }
+
+    fn part2(&self, (_, races): &Self::Input) -> Option<Answer> {
+        debug!("races: {:?}", races);
+        let victories: usize = races
+            .iter()
+            .map(|r| {
+                let vs = victories_for_each_record(*r);
+                vs.len()
+            })
+            .inspect(|v| trace!("ways to win: {}", v))
+            .product();
+
+        Some(victories.into())
+    }
+
+    fn strategies(&self, part: u8) -> Vec<Strategy<Self::Input>> {
+        match part {
+            1 => vec![("closed-form", |(races, _)| product_of_ways_to_win(races))],
+            _ => vec![("closed-form", |(_, races)| product_of_ways_to_win(races))],
+        }
+    }
+}
 
-    println!("Hello {:?}", races);
-    let victories: usize = races
-        .iter()
-        .map(|r| {
-            let vs = victories_for_each_record(*r);
-            vs.len()
-        })
-        .inspect(|v| println!("{}", v))
-        .product();
-
-    println!("\n{}", victories);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day6test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day6, EXAMPLE, 1), Some(Answer::Int(288)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day6, EXAMPLE, 2), Some(Answer::Int(71503)));
+    }
+
+    #[test]
+    fn ways_to_win_without_trying_each_one() {
+        for race in [
+            (7, 9),
+            (15, 40),
+            (30, 200),
+            (71530, 940200),
+            (10, 25),
+            (10, 24),
+        ] {
+            assert_eq!(
+                ways_to_win(race),
+                victories_for_each_record(race).len(),
+                "{:?}",
+                race
+            );
+        }
+    }
+
+    #[test]
+    fn lines_without_numbers_are_errors() {
+        let e = Day6
+            .parse("Time:\nDistance:".lines().map(String::from))
+            .unwrap_err();
+        assert_eq!((e.line, e.column), (1, 6));
+        assert_eq!(
+            (e.expected.as_str(), e.found.as_str()),
+            ("at least one number", "end of line")
+        );
+    }
 }
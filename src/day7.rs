@@ -1,15 +1,16 @@
 #![allow(dead_code, unused_mut, unused_variables)]
 
-use crate::util::read_file_as_text;
+use crate::error::{column_of, parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+use log::trace;
 use std::cmp::{Ordering, PartialOrd};
-use std::io::prelude::*;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-enum Card {
+pub enum Card {
     A = 14,
     K = 13,
     Q = 12,
-    T = 11,
+    T = 10,
     C9 = 9,
     C8 = 8,
     C7 = 7,
@@ -44,10 +45,21 @@ impl TryFrom<char> for Card {
     }
 }
 
-type CardHand = (Card, Card, Card, Card, Card);
+impl Card {
+    /// Value of the card when comparing hands. Without jokers (part 1) the
+    /// J is a jack, which goes between the Q and the T.
+    fn strength(self, jokers: bool) -> u8 {
+        match (self, jokers) {
+            (Card::J, false) => 11,
+            (c, _) => c as u8,
+        }
+    }
+}
+
+pub type CardHand = (Card, Card, Card, Card, Card);
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -113,8 +125,11 @@ fn fix_joker(mut occurences: Vec<(usize, Card)>) -> Vec<(usize, Card)> {
     }
 }
 
-fn get_hand_type(hand: &CardHand) -> HandType {
-    let occurences = fix_joker(get_card_occurences_in_hand(hand));
+pub fn get_hand_type(hand: &CardHand, jokers: bool) -> HandType {
+    let occurences = match jokers {
+        true => fix_joker(get_card_occurences_in_hand(hand)),
+        false => get_card_occurences_in_hand(hand),
+    };
 
     assert!(occurences.len() <= 5);
 
@@ -147,52 +162,158 @@ fn get_hand_type(hand: &CardHand) -> HandType {
     }
 }
 
-fn order_card_hand(left: &CardHand, right: &CardHand) -> Ordering {
-    match get_hand_type(left).cmp(&get_hand_type(right)) {
-        Ordering::Equal => left.cmp(right),
+fn hand_strengths(hand: &CardHand, jokers: bool) -> [u8; 5] {
+    let (h1, h2, h3, h4, h5) = hand;
+    [h1, h2, h3, h4, h5].map(|c| c.strength(jokers))
+}
+
+pub fn order_card_hand(left: &CardHand, right: &CardHand, jokers: bool) -> Ordering {
+    match get_hand_type(left, jokers).cmp(&get_hand_type(right, jokers)) {
+        Ordering::Equal => hand_strengths(left, jokers).cmp(&hand_strengths(right, jokers)),
         x => x,
     }
 }
 
-type CardLine = (CardHand, usize);
+pub fn total_winnings(hand_bids: &[CardLine], jokers: bool) -> usize {
+    let mut hand_bids = hand_bids.to_vec();
+    trace!("hands: {:?}", hand_bids);
 
-fn line_to_hand(line: &str) -> CardHand {
-    let mut chariter = line.chars().take(5).filter_map(|c| Card::try_from(c).ok());
+    hand_bids.sort_by(|(a, _), (b, _)| order_card_hand(a, b, jokers));
+    trace!("ranked hands: {:?}", hand_bids);
 
-    (
-        chariter.next().unwrap(),
-        chariter.next().unwrap(),
-        chariter.next().unwrap(),
-        chariter.next().unwrap(),
-        chariter.next().unwrap(),
-    )
+    hand_bids
+        .iter()
+        .enumerate()
+        .map(|(index, (_, bid))| (index + 1) * bid)
+        .sum()
 }
 
-fn line_to_cardline(line: &str) -> CardLine {
+pub type CardLine = (CardHand, usize);
+
+fn line_to_hand(line: &str, hand: &str) -> Result<CardHand, ParseError> {
+    let hand_column = column_of(line, hand);
+    let cards = hand
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            Card::try_from(c).map_err(|_| {
+                ParseError::new(
+                    hand_column + i,
+                    "a card (AKQJT98765432)",
+                    format!("{:?}", c),
+                )
+            })
+        })
+        .collect::<Result<Vec<Card>, ParseError>>()?;
+
+    match cards[..] {
+        [c1, c2, c3, c4, c5] => Ok((c1, c2, c3, c4, c5)),
+        _ => Err(ParseError::in_piece(line, hand, "a hand of 5 cards")),
+    }
+}
+
+pub fn line_to_cardline(line: &str) -> Result<CardLine, ParseError> {
     let mut parts = line.split(' ');
 
-    (
-        line_to_hand(parts.next().unwrap()),
-        parts.next().unwrap().trim().parse::<usize>().unwrap(),
-    )
+    let hand = line_to_hand(line, parts.next().unwrap())?;
+    let bid = match parts.next() {
+        Some(bid) => bid
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| ParseError::in_piece(line, bid, "a bid"))?,
+        None => return Err(ParseError::at_end(line, "a bid after the hand")),
+    };
+
+    Ok((hand, bid))
 }
 
-pub fn day7() {
-    let game_file = read_file_as_text("./inputs/day7real.txt").lines();
-    //let game_file = read_file_as_text("./inputs/day7test.txt").lines();
-    let mut hand_bids: Vec<CardLine> = game_file.map(|l| line_to_cardline(&l.unwrap())).collect();
+#[derive(Default)]
+pub struct Day7;
 
-    println!("{:?}", hand_bids);
+impl Solver for Day7 {
+    type Input = Vec<CardLine>;
 
-    hand_bids.sort_by(|(a, _), (b, _)| order_card_hand(a, b));
-    println!("{:?}", hand_bids);
+    fn day(&self) -> u8 {
+        7
+    }
 
-    let winnings: usize = hand_bids
-        .iter()
-        .enumerate()
-        .map(|(index, (_, bid))| (index + 1) * bid)
-        .sum();
-    //println!("{:?}", winnings.collect::<Vec<usize>>());
+    fn name(&self) -> &'static str {
+        "Camel Cards"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        parse_each_line(lines, line_to_cardline)
+    }
+
+    fn part1(&self, hand_bids: &Self::Input) -> Option<Answer> {
+        Some(total_winnings(hand_bids, false).into())
+    }
+
+    fn part2(&self, hand_bids: &Self::Input) -> Option<Answer> {
+        Some(total_winnings(hand_bids, true).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day7test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day7, EXAMPLE, 1), Some(Answer::Int(6440)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day7, EXAMPLE, 2), Some(Answer::Int(5905)));
+    }
 
-    println!("\n{}", winnings)
+    fn hand(cards: &str) -> CardHand {
+        line_to_hand(cards, cards).unwrap()
+    }
+
+    #[test]
+    fn hands_have_5_valid_cards() {
+        assert!(line_to_cardline("32T3K 765").is_ok());
+        assert_eq!(line_to_cardline("32T3 765").unwrap_err().column, 1);
+        assert_eq!(line_to_cardline("32T3KK 765").unwrap_err().column, 1);
+        assert_eq!(line_to_cardline("32X3K 765").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn hand_types_without_jokers() {
+        assert_eq!(get_hand_type(&hand("AAAAA"), false), HandType::FiveOfKind);
+        assert_eq!(get_hand_type(&hand("AA8AA"), false), HandType::FourOfKind);
+        assert_eq!(get_hand_type(&hand("23332"), false), HandType::FullHouse);
+        assert_eq!(get_hand_type(&hand("TTT98"), false), HandType::ThreeKind);
+        assert_eq!(get_hand_type(&hand("23432"), false), HandType::TwoPair);
+        assert_eq!(get_hand_type(&hand("A23A4"), false), HandType::OnePair);
+        assert_eq!(get_hand_type(&hand("23456"), false), HandType::HighCard);
+        assert_eq!(get_hand_type(&hand("KTJJT"), false), HandType::TwoPair);
+    }
+
+    #[test]
+    fn hand_types_with_jokers() {
+        assert_eq!(get_hand_type(&hand("KTJJT"), true), HandType::FourOfKind);
+        assert_eq!(get_hand_type(&hand("QJJQ2"), true), HandType::FourOfKind);
+        assert_eq!(get_hand_type(&hand("T55J5"), true), HandType::FourOfKind);
+        assert_eq!(get_hand_type(&hand("JJJJJ"), true), HandType::FiveOfKind);
+        assert_eq!(get_hand_type(&hand("2345J"), true), HandType::OnePair);
+    }
+
+    #[test]
+    fn fix_joker_moves_jokers_to_the_most_common_card() {
+        let occurences = get_card_occurences_in_hand(&hand("T55J5"));
+        assert_eq!(fix_joker(occurences), vec![(4, Card::C5), (1, Card::T)]);
+
+        // only jokers: nothing to move them to
+        let occurences = get_card_occurences_in_hand(&hand("JJJJJ"));
+        assert_eq!(fix_joker(occurences), vec![(5, Card::J)]);
+
+        let occurences = get_card_occurences_in_hand(&hand("23456"));
+        assert_eq!(fix_joker(occurences.clone()), occurences);
+    }
 }
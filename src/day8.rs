@@ -1,14 +1,15 @@
 #![allow(dead_code, unused_mut, unused_variables)]
 
-use crate::util::read_file_as_text;
+use crate::error::ParseError;
+use crate::solver::{Answer, Solver, Strategy};
+use log::{debug, error, trace};
 use num::integer::lcm;
 use regex::Regex;
-use std::collections::HashMap;
-use std::io::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::iter::FusedIterator;
 
 #[derive(Debug, Clone, Copy)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
@@ -25,50 +26,106 @@ impl TryFrom<char> for Direction {
     }
 }
 
-type MapNode = String;
-type MapIterResult = (MapNode, Direction);
-type MapPoint = (MapNode, MapNode);
+pub type MapNode = String;
+pub type MapIterResult = (MapNode, Direction);
+pub type MapPoint = (MapNode, MapNode);
 
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     directions: Vec<Direction>,
     nodes: HashMap<MapNode, MapPoint>,
 }
 
 impl Map {
-    fn parse_directions(line: &str) -> Vec<Direction> {
+    fn parse_directions(line: &str) -> Result<Vec<Direction>, ParseError> {
+        if line.is_empty() {
+            return Err(ParseError::at_end(line, "the directions (L or R)"));
+        }
+
         line.chars()
-            .filter_map(|c| Direction::try_from(c).ok())
+            .enumerate()
+            .map(|(i, c)| {
+                Direction::try_from(c)
+                    .map_err(|_| ParseError::new(i + 1, "a direction (L or R)", format!("{:?}", c)))
+            })
             .collect()
     }
 
-    fn parse_nodes(lines: impl Iterator<Item = String>) -> HashMap<MapNode, MapPoint> {
-        let regex = Regex::new(r"(?m)\s*([A-Z0-9]+)\s+=\s+\(([A-Z0-9]+),\s*([A-Z0-9]+)\)").unwrap();
+    fn parse_nodes(
+        lines: impl Iterator<Item = String>,
+    ) -> Result<HashMap<MapNode, MapPoint>, ParseError> {
+        let regex = Regex::new(r"([A-Z0-9]+)\s+=\s+\(([A-Z0-9]+),\s*([A-Z0-9]+)\)").unwrap();
 
         let mut map = HashMap::new();
-        for l in lines {
-            let caps = regex.captures(&l).unwrap();
-            let node = caps.get(1).unwrap().as_str().to_string();
-            let left = caps.get(2).unwrap().as_str().to_string();
-            let right = caps.get(3).unwrap().as_str().to_string();
-
-            map.insert(node, (left, right));
+        // references to other nodes, checked when every node is known
+        let mut references = vec![];
+        for (index, l) in lines.enumerate() {
+            // the nodes start after the directions and the empty line
+            let lineno = index + 3;
+            let caps = regex.captures(&l).ok_or_else(|| {
+                ParseError::in_piece(&l, &l, "a node, like \"AAA = (BBB, CCC)\"").at_line(lineno)
+            })?;
+            // the node must be the whole line, besides the spaces around it
+            let whole = caps.get(0).unwrap();
+            let before = &l[..whole.start()];
+            let after = l[whole.end()..].trim_end();
+            if !before.trim().is_empty() {
+                let junk = before.trim_start();
+                return Err(ParseError::in_piece(&l, junk, "a node").at_line(lineno));
+            }
+            if !after.is_empty() {
+                let junk = after.trim_start();
+                return Err(ParseError::in_piece(&l, junk, "the end of the line").at_line(lineno));
+            }
+
+            let node = caps.get(1).unwrap();
+            let left = caps.get(2).unwrap();
+            let right = caps.get(3).unwrap();
+
+            if map.contains_key(node.as_str()) {
+                return Err(ParseError::new(
+                    node.start() + 1,
+                    "a node defined only once",
+                    format!("{:?}", node.as_str()),
+                )
+                .at_line(lineno));
+            }
+            for m in [left, right] {
+                references.push((lineno, m.start() + 1, m.as_str().to_string()));
+            }
+
+            map.insert(
+                node.as_str().to_string(),
+                (left.as_str().to_string(), right.as_str().to_string()),
+            );
         }
 
-        map
+        match references
+            .into_iter()
+            .find(|(_, _, r)| !map.contains_key(r))
+        {
+            Some((lineno, column, node)) => {
+                Err(
+                    ParseError::new(column, "a node defined in the map", format!("{:?}", node))
+                        .at_line(lineno),
+                )
+            }
+            None => Ok(map),
+        }
     }
 
-    fn new(mut lines: impl Iterator<Item = String>) -> Self {
-        let directions = Map::parse_directions(&lines.next().unwrap());
-        let empty_line = lines.next().unwrap();
+    pub fn new(mut lines: impl Iterator<Item = String>) -> Result<Self, ParseError> {
+        let directions =
+            Map::parse_directions(&lines.next().unwrap_or_default()).map_err(|e| e.at_line(1))?;
+        let empty_line = lines.next().unwrap_or_default();
 
         if !empty_line.trim().is_empty() {
-            panic!("empty line not found... why?");
+            return Err(ParseError::in_piece(&empty_line, &empty_line, "an empty line").at_line(2));
         }
 
-        let nodes = Map::parse_nodes(lines);
+        let nodes = Map::parse_nodes(lines)?;
 
-        Map { directions, nodes }
+        Ok(Map { directions, nodes })
     }
 
     fn increase_direction(&self, current_direction: usize) -> usize {
@@ -80,24 +137,26 @@ impl Map {
         }
     }
 
-    fn simple_iter(&self) -> SimpleMapIter {
+    pub fn simple_iter(&self) -> SimpleMapIter<'_> {
         SimpleMapIter::new(self)
     }
 
-    fn simple_iter_with_custom_start(&self, start: &str) -> SimpleMapIter {
+    pub fn simple_iter_with_custom_start(&self, start: &str) -> SimpleMapIter<'_> {
         SimpleMapIter::new_with_custom_start(self, start)
     }
 
-    fn ghost_iter(&self) -> GhostMapIter {
+    pub fn ghost_iter(&self) -> GhostMapIter<'_> {
         GhostMapIter::new(self)
     }
 }
 
-struct SimpleMapIter<'a> {
+pub struct SimpleMapIter<'a> {
     map: &'a Map,
     current_direction_index: usize,
     current_node: MapNode,
     is_second_part: bool,
+    /// Nodes already left, with the index of the direction taken from them
+    seen: HashSet<(MapNode, usize)>,
 }
 
 impl<'a> SimpleMapIter<'a> {
@@ -107,6 +166,7 @@ impl<'a> SimpleMapIter<'a> {
             current_direction_index: 0,
             current_node: String::from("AAA"),
             is_second_part: false,
+            seen: HashSet::new(),
         }
     }
 
@@ -116,6 +176,7 @@ impl<'a> SimpleMapIter<'a> {
             current_direction_index: 0,
             current_node: start_node.to_string(),
             is_second_part: true,
+            seen: HashSet::new(),
         }
     }
 
@@ -125,17 +186,25 @@ impl<'a> SimpleMapIter<'a> {
             true => self.current_node.ends_with('Z'),
         }
     }
+
+    /// Steps until the end node, or `None` when the start node is not in
+    /// the map or the walk goes round in a loop without an end node
+    fn steps_to_end(mut self) -> Option<usize> {
+        let steps = self.by_ref().count();
+        self.is_end_node().then_some(steps)
+    }
 }
 
 impl<'a> Iterator for SimpleMapIter<'a> {
     type Item = MapIterResult;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.is_end_node() {
+        let state = (self.current_node.clone(), self.current_direction_index);
+        if self.is_end_node() || !self.seen.insert(state) {
             None
         } else {
             let direction = self.map.directions[self.current_direction_index];
-            let (path_left, path_right) = self.map.nodes.get(&self.current_node).unwrap();
+            let (path_left, path_right) = self.map.nodes.get(&self.current_node)?;
             let next_node = match direction {
                 Direction::Left => path_left,
                 Direction::Right => path_right,
@@ -151,10 +220,11 @@ impl<'a> Iterator for SimpleMapIter<'a> {
 
 impl<'a> FusedIterator for SimpleMapIter<'a> {}
 
-struct GhostMapIter<'a> {
+pub struct GhostMapIter<'a> {
     map: &'a Map,
     current_direction_index: usize,
     current_nodes: Vec<String>,
+    seen: HashSet<(Vec<String>, usize)>,
 }
 
 /* extremely inefficient... but cool if you wanna use iterators, or if you
@@ -172,19 +242,32 @@ impl<'a> GhostMapIter<'a> {
             map,
             current_direction_index: 0,
             current_nodes,
+            seen: HashSet::new(),
         }
     }
 
     fn is_end_node_for_all(&self) -> bool {
         self.current_nodes.iter().all(|n| n.ends_with('Z'))
     }
+
+    /// Like `SimpleMapIter::steps_to_end`, for every ghost at the same time.
+    /// Without any ghost there is no answer.
+    fn steps_to_end(mut self) -> Option<usize> {
+        if self.current_nodes.is_empty() {
+            return None;
+        }
+
+        let steps = self.by_ref().count();
+        self.is_end_node_for_all().then_some(steps)
+    }
 }
 
 impl<'a> Iterator for GhostMapIter<'a> {
     type Item = Vec<MapIterResult>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.is_end_node_for_all() {
+        let state = (self.current_nodes.clone(), self.current_direction_index);
+        if self.is_end_node_for_all() || !self.seen.insert(state) {
             None
         } else {
             let next_nodes: Vec<MapIterResult> = self
@@ -213,7 +296,7 @@ impl<'a> Iterator for GhostMapIter<'a> {
 impl<'a> FusedIterator for GhostMapIter<'a> {}
 
 // faster way to do this, use this to get the answer in your lifetime lol
-fn faster_ghost_map_count(map: &Map) -> usize {
+pub fn faster_ghost_map_count(map: &Map) -> Option<usize> {
     let current_nodes: Vec<String> = map
         .nodes
         .keys()
@@ -221,24 +304,68 @@ fn faster_ghost_map_count(map: &Map) -> usize {
         .cloned()
         .collect();
 
-    current_nodes
+    let steps: Option<Vec<usize>> = current_nodes
         .into_iter()
-        .map(|node| map.simple_iter_with_custom_start(&node).count())
-        .reduce(lcm)
-        .unwrap()
+        .map(|node| map.simple_iter_with_custom_start(&node).steps_to_end())
+        .collect();
+
+    steps?.into_iter().reduce(lcm)
 }
 
-pub fn day8() {
-    let game_file = read_file_as_text("./inputs/day8real.txt").lines();
-    let map = Map::new(game_file.map(|s| s.unwrap()));
+#[derive(Default)]
+pub struct Day8;
 
-    println!("Hello, {:?}", map);
+impl Solver for Day8 {
+    type Input = Map;
 
-    let nums = map
-        .simple_iter()
-        .inspect(|(node, dir)| println!("{} {:?}", node, dir))
-        .count();
-    println!("\n{}", nums);
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn name(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        Map::new(lines)
+    }
+
+    fn part1(&self, map: &Self::Input) -> Option<Answer> {
+        debug!("{:?}", map);
+
+        if !map.nodes.contains_key("AAA") {
+            error!("there is no AAA node to start from");
+            return None;
+        }
+
+        let mut walk = map.simple_iter();
+        let steps = walk
+            .by_ref()
+            .inspect(|(node, dir)| trace!("{} {:?}", node, dir))
+            .count();
+
+        if !walk.is_end_node() {
+            error!("ZZZ cannot be reached from AAA");
+            return None;
+        }
+        Some(steps.into())
+    }
+
+    fn part2(&self, map: &Self::Input) -> Option<Answer> {
+        debug!("{:?}", map);
+
+        Some(faster_ghost_map_count(map)?.into())
+    }
+
+    fn strategies(&self, part: u8) -> Vec<Strategy<Map>> {
+        match part {
+            // walks every ghost at the same time, too slow for the real input
+            2 => vec![("ghost-iter", |map| {
+                Some(map.ghost_iter().steps_to_end()?.into())
+            })],
+            _ => vec![],
+        }
+    }
 }
 
 use console_engine::pixel;
@@ -293,9 +420,8 @@ fn draw_directions(scr: &mut Screen, mut dirs: impl Iterator<Item = Direction>)
     }
 }
 
-pub fn day8view() {
-    let game_file = read_file_as_text("./inputs/day8real.txt").lines();
-    let map = Map::new(game_file.map(|s| s.unwrap()));
+pub fn day8view(lines: impl Iterator<Item = String>) -> Result<(), ParseError> {
+    let map = Map::new(lines)?;
 
     let mut engine = console_engine::ConsoleEngine::init(70, 20, 5).unwrap();
     engine.wait_frame();
@@ -335,8 +461,7 @@ jgs`.__.'"#,
 
     draw_screens(&graph_str, &icon_str, &dir_str, &mut engine);
 
-    let mut idx = 0;
-    for m in map.simple_iter() {
+    for (idx, m) in map.simple_iter().enumerate() {
         thread::sleep(time::Duration::from_millis(300));
         draw_directions(
             &mut dir_str,
@@ -376,22 +501,70 @@ jgs`.__.'"#,
 
         thread::sleep(time::Duration::from_millis(300));
         draw_screens(&graph_str, &icon_str, &dir_str, &mut engine);
-        idx += 1;
     }
 
-    //let nums = map.simple_iter().inspect(|(node, dir)| println!("{} {:?}", node, dir)).count();
-    //println!("\n{}", nums);
+    Ok(())
 }
 
-pub fn day8t2() {
-    let game_file = read_file_as_text("./inputs/day8real.txt").lines();
-    let map = Map::new(game_file.map(|s| s.unwrap()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day8test.txt");
+    const EXAMPLE2: &str = include_str!("../inputs/day8test2.txt");
+    const EXAMPLE3: &str = include_str!("../inputs/day8test3.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day8, EXAMPLE, 1), Some(Answer::Int(2)));
+    }
+
+    #[test]
+    fn part1_example2() {
+        assert_eq!(solve_example(&Day8, EXAMPLE2, 1), Some(Answer::Int(6)));
+    }
+
+    #[test]
+    fn part2_example3() {
+        assert_eq!(solve_example(&Day8, EXAMPLE3, 2), Some(Answer::Int(6)));
+    }
 
-    println!("Hello, {:?}", map);
+    #[test]
+    fn nodes_must_be_defined() {
+        let map = |text: &str| Map::new(text.lines().map(String::from));
 
-    //let nums = map.ghost_iter().inspect(|nodes| println!("{:?}", nodes)).count();
-    //println!("\n{}", nums);
+        let e = map("LR\n\nAAA = (BBB, CCC)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (3, 13, "\"CCC\""));
 
-    let nums = faster_ghost_map_count(&map);
-    println!("\n{}", nums);
+        let e = map("L\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!((e.line, e.column), (5, 1));
+    }
+
+    #[test]
+    fn walks_without_an_end_have_no_answer() {
+        // no AAA node
+        assert_eq!(solve_example(&Day8, EXAMPLE3, 1), None);
+        // ZZZ cannot be reached from AAA, nor 11Z from 11A
+        let text = "L\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(solve_example(&Day8, text, 1), None);
+        let text = "LR\n\n11A = (11B, 11Z)\n11B = (11A, 11A)\n11Z = (11Z, 11Z)";
+        assert_eq!(solve_example(&Day8, text, 2), None);
+        // no ghosts
+        let text = "L\n\nBBB = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(solve_example(&Day8, text, 2), None);
+    }
+
+    #[test]
+    fn nodes_are_the_whole_line() {
+        let map = |text: &str| Map::new(text.lines().map(String::from));
+
+        let e = map("L\n\nxAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (3, 1, "\"x\""));
+
+        let e = map("L\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ) ; loop").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (4, 18, "\"; loop\""));
+
+        assert!(map("L\n\n AAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ) ").is_ok());
+    }
 }
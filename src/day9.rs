@@ -1,9 +1,11 @@
 #![allow(dead_code, unused_mut, unused_variables)]
 
-use crate::util::{read_file_as_text, split_numbers_by_space};
-use std::io::prelude::*;
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+use crate::util::parse_numbers;
+use log::{debug, trace};
 
-struct OASISSequenceIter {
+pub struct OASISSequenceIter {
     derivatives: Vec<isize>,
 }
 
@@ -16,7 +18,7 @@ impl OASISSequenceIter {
             .collect();
         derivatives.sort();
 
-        eprintln!("c {:?}", derivatives);
+        trace!("derivatives {:?}", derivatives);
         Self { derivatives }
     }
 
@@ -29,7 +31,7 @@ impl OASISSequenceIter {
             ret.push(last_solved + v);
         }
 
-        return ret;
+        ret
     }
 }
 
@@ -38,7 +40,7 @@ impl Iterator for OASISSequenceIter {
 
     fn next(&mut self) -> Option<Self::Item> {
         let next_derivatives = self.generate_next_values();
-        eprintln!("nn {:?}", next_derivatives);
+        trace!("next derivatives {:?}", next_derivatives);
 
         let ret = next_derivatives.last().cloned();
 
@@ -49,12 +51,12 @@ impl Iterator for OASISSequenceIter {
 }
 
 #[derive(Debug)]
-struct OASISSequence {
+pub struct OASISSequence {
     start: Vec<isize>,
 }
 
 impl OASISSequence {
-    fn new(elements: impl Iterator<Item = isize>) -> Self {
+    pub fn new(elements: impl Iterator<Item = isize>) -> Self {
         Self {
             start: elements.collect(),
         }
@@ -64,16 +66,16 @@ impl OASISSequence {
         (last as isize) - (second_to_last as isize)
     }
 
-    fn generate_single_derivative(input: &Vec<isize>) -> Vec<isize> {
+    fn generate_single_derivative(input: &[isize]) -> Vec<isize> {
         input.windows(2).map(|w| w[1] - w[0]).collect()
     }
 
-    fn calculate_derivatives(start: &Vec<isize>) -> Vec<Vec<isize>> {
+    fn calculate_derivatives(start: &[isize]) -> Vec<Vec<isize>> {
         let mut derivatives: Vec<Vec<isize>> = Vec::new();
 
         loop {
-            let last_sequence = derivatives.last().unwrap_or(start);
-            derivatives.push(OASISSequence::generate_single_derivative(&last_sequence));
+            let last_sequence = derivatives.last().map(Vec::as_slice).unwrap_or(start);
+            derivatives.push(OASISSequence::generate_single_derivative(last_sequence));
 
             assert!(derivatives.last().is_some());
             if *derivatives.last().unwrap().last().unwrap_or(&0) == 0 {
@@ -84,12 +86,12 @@ impl OASISSequence {
         derivatives
     }
 
-    fn calculate_previous_derivatives(start: &Vec<isize>) -> Vec<Vec<isize>> {
+    fn calculate_previous_derivatives(start: &[isize]) -> Vec<Vec<isize>> {
         let mut derivatives: Vec<Vec<isize>> = Vec::new();
 
         loop {
-            let last_sequence = derivatives.last().unwrap_or(start);
-            derivatives.push(OASISSequence::generate_single_derivative(&last_sequence));
+            let last_sequence = derivatives.last().map(Vec::as_slice).unwrap_or(start);
+            derivatives.push(OASISSequence::generate_single_derivative(last_sequence));
 
             if *derivatives.last().unwrap().first().unwrap_or(&0) == 0 {
                 break;
@@ -99,12 +101,12 @@ impl OASISSequence {
         derivatives
     }
 
-    fn generate_derivative_iter(&self) -> OASISSequenceIter {
+    pub fn generate_derivative_iter(&self) -> OASISSequenceIter {
         let derivatives = OASISSequence::calculate_derivatives(&self.start);
         OASISSequenceIter::new(self.start.last().cloned().unwrap() as usize, derivatives)
     }
 
-    fn generate_derivative_previous_iter(&self) -> OASISSequenceIter {
+    pub fn generate_derivative_previous_iter(&self) -> OASISSequenceIter {
         let mut start_rev = self.start.clone();
         start_rev.reverse();
 
@@ -113,36 +115,83 @@ impl OASISSequence {
     }
 }
 
-pub fn day9() {
-    //let game_file = read_file_as_text("./inputs/day9test.txt").lines();
-    let game_file = read_file_as_text("./inputs/day9real.txt").lines();
-    let sequences = game_file
-        .map(|s| OASISSequence::new(split_numbers_by_space(&s.unwrap()).into_iter()))
-        .inspect(|s| println!("s {:?}", s));
-
-    let derivatives = sequences
-        .map(|s| (s.generate_derivative_previous_iter()))
-        .map(|mut d| d.next().unwrap());
-    let next_sequences = derivatives
-        .inspect(|v| eprintln!("n {:?}", v))
-        .collect::<Vec<isize>>();
-
-    println!("{}", next_sequences.into_iter().sum::<isize>());
+#[derive(Default)]
+pub struct Day9;
+
+impl Solver for Day9 {
+    type Input = Vec<OASISSequence>;
+
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn name(&self) -> &'static str {
+        "Mirage Maintenance"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        parse_each_line(lines, |line| {
+            let numbers: Vec<isize> = parse_numbers(line, line, ' ', "a number")?;
+            if numbers.is_empty() {
+                return Err(ParseError::in_piece(line, line, "a sequence of numbers"));
+            }
+
+            Ok(OASISSequence::new(numbers.into_iter()))
+        })
+        .inspect(|s| debug!("sequence {:?}", s))
+    }
+
+    fn part1(&self, sequences: &Self::Input) -> Option<Answer> {
+        let derivatives = sequences
+            .iter()
+            .map(|s| s.generate_derivative_iter())
+            .map(|mut d| d.next().unwrap());
+        let next_sequences = derivatives
+            .inspect(|v| trace!("next value {:?}", v))
+            .collect::<Vec<isize>>();
+
+        Some(next_sequences.into_iter().sum::<isize>().into())
+    }
+
+    fn part2(&self, sequences: &Self::Input) -> Option<Answer> {
+        let derivatives = sequences
+            .iter()
+            .map(|s| s.generate_derivative_previous_iter())
+            .map(|mut d| d.next().unwrap());
+        let next_sequences = derivatives
+            .inspect(|v| trace!("next value {:?}", v))
+            .collect::<Vec<isize>>();
+
+        Some(next_sequences.into_iter().sum::<isize>().into())
+    }
 }
 
-pub fn day9t1() {
-    //let game_file = read_file_as_text("./inputs/day9test.txt").lines();
-    let game_file = read_file_as_text("./inputs/day9real.txt").lines();
-    let sequences = game_file
-        .map(|s| OASISSequence::new(split_numbers_by_space(&s.unwrap()).into_iter()))
-        .inspect(|s| println!("s {:?}", s));
-
-    let derivatives = sequences
-        .map(|s| (s.generate_derivative_iter()))
-        .map(|mut d| d.next().unwrap());
-    let next_sequences = derivatives
-        .inspect(|v| eprintln!("n {:?}", v))
-        .collect::<Vec<isize>>();
-
-    println!("{}", next_sequences.into_iter().sum::<isize>());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("../inputs/day9test.txt");
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day9, EXAMPLE, 1), Some(Answer::Int(114)));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day9, EXAMPLE, 2), Some(Answer::Int(2)));
+    }
+
+    #[test]
+    fn negative_numbers() {
+        let sequences = "-1 -3 -5 -7\n4 1 -2";
+        assert_eq!(solve_example(&Day9, sequences, 1), Some(Answer::Int(-14)));
+        assert_eq!(solve_example(&Day9, sequences, 2), Some(Answer::Int(8)));
+
+        let e = Day9
+            .parse("1 2 3\n4 5-6".lines().map(String::from))
+            .unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (2, 3, "\"5-6\""));
+    }
 }
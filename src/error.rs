@@ -0,0 +1,96 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error found while parsing a puzzle input.
+///
+/// Line parsers only know the column, the loops over the lines fill the
+/// line number, and the day is filled when the solver is called through
+/// the registry. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, expected: impl Into<String>, found: impl Into<String>) -> Self {
+        ParseError {
+            day: 0,
+            line: 0,
+            column,
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    /// Error about `piece`, which must be a slice of `line`. The column is
+    /// where the piece starts.
+    pub fn in_piece(line: &str, piece: &str, expected: impl Into<String>) -> Self {
+        let column = column_of(line, piece);
+        let found = match (piece.is_empty(), line[column - 1..].chars().next()) {
+            (false, _) => format!("{:?}", piece),
+            (true, Some(c)) => format!("{:?}", c),
+            (true, None) => String::from("end of line"),
+        };
+
+        ParseError::new(column, expected, found)
+    }
+
+    /// Error about something missing at the end of `line`
+    pub fn at_end(line: &str, expected: impl Into<String>) -> Self {
+        ParseError::new(line.len() + 1, expected, "end of line")
+    }
+
+    /// Error about something missing after the last line of the input,
+    /// which had `lines` lines
+    pub fn at_end_of_input(lines: usize, expected: impl Into<String>) -> Self {
+        ParseError::new(1, expected, "end of input").at_line(lines + 1)
+    }
+
+    pub fn at_line(self, line: usize) -> Self {
+        ParseError { line, ..self }
+    }
+
+    pub fn for_day(self, day: u8) -> Self {
+        ParseError { day, ..self }
+    }
+}
+
+/// Column (starting at 1) where `piece` starts, when `piece` was sliced
+/// from `line`. Anything else is reported at column 1.
+pub fn column_of(line: &str, piece: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let pos = piece.as_ptr() as usize;
+
+    if pos >= start && pos <= start + line.len() {
+        pos - start + 1
+    } else {
+        1
+    }
+}
+
+/// Parse every line with `f`, filling the line number of the errors.
+pub fn parse_each_line<T>(
+    lines: impl Iterator<Item = String>,
+    f: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines
+        .enumerate()
+        .map(|(index, line)| f(&line).map_err(|e| e.at_line(index + 1)))
+        .collect()
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}, found {}",
+            self.day, self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,570 @@
+//! Random inputs that follow the format and the rules of each puzzle, for
+//! stress tests and benchmarks without the real inputs. The same day, size
+//! and seed always give the same input.
+
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seed for when none is given, from the clock
+pub fn random_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    now.as_nanos() as u64
+}
+
+/// SplitMix64, which is small, fast and good enough for making up inputs
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `min..=max`
+    pub fn between(&mut self, min: usize, max: usize) -> usize {
+        min + self.below(max - min + 1)
+    }
+
+    /// True one time out of `n`
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+pub type Generator = fn(usize, &mut Rng) -> Vec<String>;
+
+/// The generator of each day, with the size used when none is given. What
+/// the size means depends on the day.
+const GENERATORS: [(u8, usize, Generator); 12] = [
+    (1, 1000, calibration_lines),
+    (2, 100, games),
+    (3, 140, schematic),
+    (4, 200, scratchcards),
+    (5, 30, almanac),
+    (6, 3, races),
+    (7, 1000, hands),
+    (8, 700, network),
+    (9, 200, histories),
+    (10, 140, pipe_maze),
+    (11, 140, galaxy_image),
+    (12, 1000, spring_rows),
+];
+
+/// Days with a generator
+pub fn days() -> Vec<u8> {
+    GENERATORS.iter().map(|(day, _, _)| *day).collect()
+}
+
+/// Size of the inputs of a day when none is given
+pub fn default_size(day: u8) -> Option<usize> {
+    GENERATORS
+        .iter()
+        .find(|(d, _, _)| *d == day)
+        .map(|(_, size, _)| *size)
+}
+
+/// Generate an input for a day, or `None` if the day has no generator
+pub fn generate(day: u8, size: Option<usize>, seed: u64) -> Option<Vec<String>> {
+    let (_, default_size, generator) = GENERATORS.iter().find(|(d, _, _)| *d == day)?;
+    let mut rng = Rng::new(seed);
+
+    Some(generator(size.unwrap_or(*default_size).max(1), &mut rng))
+}
+
+const DIGIT_NAMES: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `size` lines of letters, digits and spelled digits, with at least one
+/// digit each
+fn calibration_lines(size: usize, rng: &mut Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let mut line = String::new();
+            for _ in 0..rng.between(1, 6) {
+                match rng.below(3) {
+                    0 => line.push((b'0' + rng.between(1, 9) as u8) as char),
+                    1 => line += *rng.pick(&DIGIT_NAMES),
+                    _ => line.push((b'a' + rng.below(26) as u8) as char),
+                }
+            }
+
+            let digit = (b'0' + rng.between(1, 9) as u8) as char;
+            line.insert(rng.below(line.len() + 1), digit);
+            line
+        })
+        .collect()
+}
+
+/// `size` games of up to 6 sets
+fn games(size: usize, rng: &mut Rng) -> Vec<String> {
+    (1..=size)
+        .map(|id| {
+            let sets: Vec<String> = (0..rng.between(1, 6))
+                .map(|_| {
+                    let mut colors = ["red", "green", "blue"];
+                    rng.shuffle(&mut colors);
+
+                    colors[..rng.between(1, 3)]
+                        .iter()
+                        .map(|color| format!("{} {}", rng.between(1, 20), color))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect();
+
+            format!("Game {}: {}", id, sets.join("; "))
+        })
+        .collect()
+}
+
+/// A `size` by `size` schematic of numbers and symbols
+fn schematic(size: usize, rng: &mut Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let mut row = String::new();
+            while row.len() < size {
+                match rng.below(8) {
+                    0 | 1 => {
+                        let number = rng.between(1, 999).to_string();
+                        row += &number[..number.len().min(size - row.len())];
+                        // numbers in the same row are not next to each other
+                        row.push('.');
+                    }
+                    2 => row.push(*rng.pick(&['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'])),
+                    _ => row.push('.'),
+                }
+            }
+
+            row.truncate(size);
+            row
+        })
+        .collect()
+}
+
+/// `size` cards with 10 winning numbers and 25 numbers each. Most cards
+/// match few numbers, or the copies of part 2 would grow out of bounds.
+fn scratchcards(size: usize, rng: &mut Rng) -> Vec<String> {
+    let join = |numbers: &[usize]| {
+        numbers
+            .iter()
+            .map(|n| format!("{:>2}", n))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    (1..=size)
+        .map(|id| {
+            let matches = match rng.below(50) {
+                0 => rng.between(5, 10),
+                1..=12 => rng.between(1, 4),
+                _ => 0,
+            };
+
+            let mut all: Vec<usize> = (1..100).collect();
+            rng.shuffle(&mut all);
+            let (winning, others) = all.split_at(10);
+            let mut numbers: Vec<usize> = winning[..matches]
+                .iter()
+                .chain(&others[..25 - matches])
+                .copied()
+                .collect();
+            rng.shuffle(&mut numbers);
+
+            format!("Card {:>3}: {} | {}", id, join(winning), join(&numbers))
+        })
+        .collect()
+}
+
+/// Headers of the maps of day 5, in order
+const ALMANAC_MAPS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+/// An almanac with `size` ranges in each map. The sources of a map cover
+/// `0..2^32` without overlapping, and so do the destinations.
+fn almanac(size: usize, rng: &mut Rng) -> Vec<String> {
+    const END: usize = 1 << 32;
+
+    let seeds: Vec<String> = (0..10)
+        .map(|_| {
+            let length = rng.between(1, size * 1000);
+            format!("{} {}", rng.below(END - length), length)
+        })
+        .collect();
+
+    let mut lines = vec![format!("seeds: {}", seeds.join(" "))];
+    for name in ALMANAC_MAPS {
+        let mut cuts: Vec<usize> = (1..size).map(|_| rng.between(1, END - 1)).collect();
+        cuts.extend([0, END]);
+        cuts.sort();
+        cuts.dedup();
+
+        // (source start, length), laid out again in another order for the
+        // destinations
+        let sources: Vec<(usize, usize)> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        let mut order: Vec<usize> = (0..sources.len()).collect();
+        rng.shuffle(&mut order);
+
+        let mut destination = 0;
+        let mut ranges: Vec<String> = order
+            .into_iter()
+            .map(|i| {
+                let (source, length) = sources[i];
+                let range = format!("{} {} {}", destination, source, length);
+                destination += length;
+                range
+            })
+            .collect();
+        rng.shuffle(&mut ranges);
+
+        lines.push(String::new());
+        lines.push(format!("{} map:", name));
+        lines.extend(ranges);
+    }
+
+    lines
+}
+
+/// `size` races that can be won. Part 2 joins all of them in a single race,
+/// so it gets slow quickly.
+fn races(size: usize, rng: &mut Rng) -> Vec<String> {
+    let races: Vec<(usize, usize)> = (0..size)
+        .map(|_| {
+            let time = rng.between(7, 99);
+            (time, rng.below(time * time / 4))
+        })
+        .collect();
+
+    let column = |values: Vec<usize>| {
+        values
+            .iter()
+            .map(|v| format!("{:>5}", v))
+            .collect::<String>()
+    };
+
+    vec![
+        format!("Time:    {}", column(races.iter().map(|r| r.0).collect())),
+        format!("Distance:{}", column(races.iter().map(|r| r.1).collect())),
+    ]
+}
+
+/// `size` hands with their bids
+fn hands(size: usize, rng: &mut Rng) -> Vec<String> {
+    let cards: Vec<char> = "AKQJT98765432".chars().collect();
+
+    (0..size)
+        .map(|_| {
+            let hand: String = (0..5).map(|_| *rng.pick(&cards)).collect();
+            format!("{} {}", hand, rng.between(1, 1000))
+        })
+        .collect()
+}
+
+/// A network with about `size` nodes where `AAA` reaches `ZZZ`, and up to 5
+/// other `..A` nodes reach a `..Z` node.
+///
+/// Each path is a chain of new nodes, as long as a multiple of the
+/// directions, and its end goes where its start goes. So after reaching the
+/// end every path repeats itself, like in the real inputs.
+fn network(size: usize, rng: &mut Rng) -> Vec<String> {
+    // there are only so many names
+    let size = size.min(10000);
+    let directions: String = (0..rng.between(2, 20).min(size))
+        .map(|_| *rng.pick(&['L', 'R']))
+        .collect();
+    let laps = (size / 6 / directions.len()).max(1);
+
+    let letter = |rng: &mut Rng| (b'B' + rng.below(24) as u8) as char;
+    let mut used: HashSet<String> = HashSet::new();
+    let mut new_name = |rng: &mut Rng, last: char| loop {
+        let name: String = [letter(rng), letter(rng), last].iter().collect();
+        if used.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    // (node, left, right)
+    let mut nodes: Vec<(String, String, String)> = vec![];
+    // (end, index of the start)
+    let mut ends = vec![];
+    for path in 0..rng.between(1, 6) {
+        let (start, end) = match path {
+            0 => (String::from("AAA"), String::from("ZZZ")),
+            _ => (new_name(rng, 'A'), new_name(rng, 'Z')),
+        };
+
+        let length = directions.len() * rng.between(1, laps);
+        let mut chain = vec![start];
+        for _ in 1..length {
+            let last = letter(rng);
+            chain.push(new_name(rng, last));
+        }
+        chain.push(end.clone());
+
+        let first = nodes.len();
+        for (step, pair) in chain.windows(2).enumerate() {
+            // the other way is filled later, when every node exists
+            let (left, right) = match directions.as_bytes()[step % directions.len()] {
+                b'L' => (pair[1].clone(), String::new()),
+                _ => (String::new(), pair[1].clone()),
+            };
+            nodes.push((pair[0].clone(), left, right));
+        }
+
+        ends.push((end, first));
+    }
+
+    let names: Vec<String> = nodes.iter().map(|(n, _, _)| n.clone()).collect();
+    for (_, left, right) in &mut nodes {
+        for way in [left, right] {
+            if way.is_empty() {
+                *way = rng.pick(&names).clone();
+            }
+        }
+    }
+
+    // the end of a path goes where its start goes
+    for (end, start) in ends {
+        let (_, left, right) = nodes[start].clone();
+        nodes.push((end, left, right));
+    }
+    rng.shuffle(&mut nodes);
+
+    let mut lines = vec![directions, String::new()];
+    lines.extend(
+        nodes
+            .into_iter()
+            .map(|(node, left, right)| format!("{} = ({}, {})", node, left, right)),
+    );
+    lines
+}
+
+/// `size` histories of 21 values of polynomials up to the 5th degree
+fn histories(size: usize, rng: &mut Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let coefficients: Vec<i64> = (0..rng.between(1, 6))
+                .map(|_| rng.between(0, 20) as i64 - 10)
+                .collect();
+
+            (0..21)
+                .map(|x: i64| {
+                    coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |value, c| value * (x - 5) + c)
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// A `size` by `size` map with a single loop, and pipes that are not part of
+/// it around.
+///
+/// The loop is the border of a shape made of columns of random heights, where
+/// each column overlaps the next one, so the border never touches itself.
+fn pipe_maze(size: usize, rng: &mut Rng) -> Vec<String> {
+    let size = size.max(3);
+    // the cells of the shape are between the tiles of the map
+    let cells = size - 1;
+    let first = rng.below(cells - 1);
+    let last = rng.between(first + 1, cells - 1);
+
+    let mut columns: Vec<(usize, usize)> = vec![];
+    for _ in first..=last {
+        let (top, bottom) = match columns.last() {
+            None => {
+                let top = rng.below(cells);
+                (top, rng.between(top, cells - 1))
+            }
+            Some(&(prev_top, prev_bottom)) => {
+                let top = rng.between(0, prev_bottom);
+                let bottom = rng.between(top.max(prev_top), cells - 1);
+                (top, bottom)
+            }
+        };
+        columns.push((top, bottom));
+    }
+    let inside = |x: isize, y: isize| {
+        x >= first as isize
+            && x <= last as isize
+            && y >= 0
+            && columns[x as usize - first].0 as isize <= y
+            && y <= columns[x as usize - first].1 as isize
+    };
+
+    let junk = ['|', '-', 'L', 'J', '7', 'F', '.', '.', '.'];
+    let mut map: Vec<Vec<char>> = (0..size)
+        .map(|_| (0..size).map(|_| *rng.pick(&junk)).collect())
+        .collect();
+
+    // each tile is a corner of 4 cells, and the pipe follows the borders
+    // between the ones inside the shape and the ones outside
+    let mut tiles_of_loop = vec![];
+    for (y, row) in map.iter_mut().enumerate() {
+        for (x, tile) in row.iter_mut().enumerate() {
+            let (x, y) = (x as isize, y as isize);
+            let top_left = inside(x - 1, y - 1);
+            let top_right = inside(x, y - 1);
+            let bottom_left = inside(x - 1, y);
+            let bottom_right = inside(x, y);
+
+            let north = top_left != top_right;
+            let south = bottom_left != bottom_right;
+            let west = top_left != bottom_left;
+            let east = top_right != bottom_right;
+
+            let pipe = match (north, south, west, east) {
+                (true, true, false, false) => '|',
+                (false, false, true, true) => '-',
+                (true, false, false, true) => 'L',
+                (true, false, true, false) => 'J',
+                (false, true, true, false) => '7',
+                (false, true, false, true) => 'F',
+                _ => continue,
+            };
+            *tile = pipe;
+            tiles_of_loop.push((x as usize, y as usize));
+        }
+    }
+
+    let &(x, y) = rng.pick(&tiles_of_loop);
+    map[y][x] = 'S';
+    map.into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect()
+}
+
+/// A `size` by `size` image with a galaxy in about one of every 50 places
+fn galaxy_image(size: usize, rng: &mut Rng) -> Vec<String> {
+    let mut image: Vec<Vec<char>> = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.one_in(50) { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+
+    // at least a pair to measure
+    for _ in 0..2 {
+        let (x, y) = (rng.below(size), rng.below(size));
+        image[y][x] = '#';
+    }
+
+    image
+        .into_iter()
+        .map(|row| row.into_iter().collect())
+        .collect()
+}
+
+/// `size` rows of up to 20 springs, with about half of them unknown
+fn spring_rows(size: usize, rng: &mut Rng) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let length = rng.between(1, 20);
+            let mut springs: Vec<char> = (0..length)
+                .map(|_| if rng.one_in(2) { '#' } else { '.' })
+                .collect();
+            springs[rng.below(length)] = '#';
+
+            let counts: Vec<String> = springs
+                .split(|&c| c == '.')
+                .filter(|group| !group.is_empty())
+                .map(|group| group.len().to_string())
+                .collect();
+
+            for spring in springs.iter_mut() {
+                if rng.one_in(2) {
+                    *spring = '?';
+                }
+            }
+
+            format!(
+                "{} {}",
+                springs.into_iter().collect::<String>(),
+                counts.join(",")
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn inputs_parse_and_solve() {
+        for solver in registry() {
+            // part 2 of day 6 joins the races in one, twice as long for
+            // each race
+            let sizes = match solver.day() {
+                6 => vec![1, 3],
+                _ => vec![1, 3, 15],
+            };
+
+            for (seed, size) in sizes.into_iter().enumerate() {
+                let Some(lines) = generate(solver.day(), Some(size), seed as u64) else {
+                    continue;
+                };
+
+                let input = solver
+                    .parse(&mut lines.clone().into_iter())
+                    .unwrap_or_else(|e| panic!("{}\n{}", e, lines.join("\n")));
+                solver.part1(&*input);
+                solver.part2(&*input);
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_input() {
+        assert_eq!(generate(7, None, 42), generate(7, None, 42));
+        assert_ne!(generate(7, None, 42), generate(7, None, 43));
+        assert_eq!(generate(30, None, 42), None);
+    }
+
+    #[test]
+    fn networks_reach_the_end() {
+        use crate::day8::Map;
+
+        for seed in 0..20 {
+            let lines = generate(8, Some(100), seed).unwrap();
+            let map = Map::new(lines.into_iter()).unwrap();
+            assert!(map.simple_iter().count() > 0);
+        }
+    }
+}
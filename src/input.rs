@@ -0,0 +1,223 @@
+//! Puzzle inputs, read from a file (decompressed when it is gzipped), from
+//! stdin or from a string.
+
+use crate::util::input_path;
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+
+pub struct Input {
+    /// Where the input comes from, for the errors
+    name: String,
+    reader: Box<dyn BufRead>,
+}
+
+impl Input {
+    /// Open a file. Files ending in `.gz` are decompressed while reading.
+    pub fn open(path: impl AsRef<Path>) -> Result<Input, String> {
+        let path = path.as_ref();
+        let name = path.display().to_string();
+        let file = File::open(path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => format!("Input not found: {}", name),
+            _ => format!("Could not open {}: {}", name, e),
+        })?;
+
+        let reader: Box<dyn BufRead> = match path.extension() {
+            Some(extension) if extension == "gz" => {
+                Box::new(BufReader::new(MultiGzDecoder::new(file)))
+            }
+            _ => Box::new(BufReader::new(file)),
+        };
+
+        Ok(Input { name, reader })
+    }
+
+    pub fn stdin() -> Input {
+        Input {
+            name: String::from("stdin"),
+            reader: Box::new(BufReader::new(io::stdin())),
+        }
+    }
+
+    /// Input already in memory, like the examples in the tests
+    pub fn from_text(text: impl Into<String>) -> Input {
+        Input {
+            name: String::from("text"),
+            reader: Box::new(Cursor::new(text.into())),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn read_error(name: &str, e: io::Error) -> String {
+        format!("Could not read {}: {}", name, e)
+    }
+
+    /// The whole input
+    pub fn text(mut self) -> Result<String, String> {
+        let mut text = String::new();
+        self.reader
+            .read_to_string(&mut text)
+            .map_err(|e| Input::read_error(&self.name, e))?;
+
+        Ok(text)
+    }
+
+    /// Every line, without the line endings
+    pub fn lines(self) -> Result<Vec<String>, String> {
+        let name = self.name;
+        self.reader
+            .lines()
+            .collect::<Result<_, _>>()
+            .map_err(|e| Input::read_error(&name, e))
+    }
+
+    /// Blocks of lines separated by blank lines, without the blank lines
+    pub fn paragraphs(self) -> Result<Vec<Vec<String>>, String> {
+        let lines = self.lines()?;
+
+        Ok(lines
+            .split(|line| line.trim().is_empty())
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| paragraph.to_vec())
+            .collect())
+    }
+}
+
+/// Where the puzzle input comes from
+#[derive(Debug, PartialEq)]
+pub enum InputSource {
+    /// One of the files in the inputs directory, like `real` or `test`
+    Variant {
+        dir: String,
+        variant: String,
+    },
+    File(String),
+    Stdin,
+}
+
+impl InputSource {
+    /// Name of the input in the answers file, like `inputs/day5real.txt`.
+    /// The same file has the same name however its path is written.
+    pub fn name(&self, day: u8) -> String {
+        match self {
+            InputSource::Variant { dir, variant } => clean_path(&input_path(dir, day, variant)),
+            InputSource::File(path) => clean_path(path),
+            InputSource::Stdin => String::from("-"),
+        }
+    }
+
+    pub fn is_available(&self, day: u8) -> bool {
+        match self {
+            InputSource::Variant { dir, variant } => {
+                Path::new(&input_path(dir, day, variant)).exists()
+            }
+            InputSource::File(path) => Path::new(path).exists(),
+            InputSource::Stdin => true,
+        }
+    }
+
+    pub fn open(&self, day: u8) -> Result<Input, String> {
+        match self {
+            InputSource::Variant { dir, variant } => Input::open(input_path(dir, day, variant)),
+            InputSource::File(path) => Input::open(path),
+            InputSource::Stdin => Ok(Input::stdin()),
+        }
+    }
+}
+
+/// `path` without `.` and `..` components, and relative to the current
+/// directory when it is inside it, like `inputs/day5real.txt` for
+/// `./inputs/../inputs/day5real.txt`
+fn clean_path(path: &str) -> String {
+    let mut clean = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match clean.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    clean.pop();
+                }
+                _ => clean.push(".."),
+            },
+            component => clean.push(component),
+        }
+    }
+
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|dir| clean.strip_prefix(dir).ok().map(Path::to_path_buf));
+    relative.unwrap_or(clean).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::fs;
+    use std::io::Write;
+
+    const TEXT: &str = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\n";
+
+    #[test]
+    fn lines_text_and_paragraphs() {
+        assert_eq!(Input::from_text(TEXT).text().unwrap(), TEXT);
+        assert_eq!(Input::from_text(TEXT).lines().unwrap().len(), 7);
+        assert_eq!(
+            Input::from_text(TEXT).paragraphs().unwrap(),
+            vec![
+                vec!["seeds: 79 14"],
+                vec!["seed-to-soil map:", "50 98 2", "52 50 48"]
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_files_are_errors() {
+        let e = Input::open("inputs/day99nothing.txt").err().unwrap();
+        assert_eq!(e, "Input not found: inputs/day99nothing.txt");
+    }
+
+    #[test]
+    fn gzipped_files_are_decompressed() {
+        let dir = std::env::temp_dir().join(format!("aoc2023-gzip-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("day5big.txt.gz");
+
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(TEXT.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let lines = Input::open(&path).unwrap().lines().unwrap();
+        assert_eq!(lines, Input::from_text(TEXT).lines().unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn inputs_have_the_same_name_however_the_path_is_written() {
+        let name = |path: &str| InputSource::File(path.to_string()).name(5);
+        let variant = InputSource::Variant {
+            dir: String::from("./inputs/"),
+            variant: String::from("real"),
+        };
+
+        assert_eq!(variant.name(5), "inputs/day5real.txt");
+        assert_eq!(name("inputs/day5real.txt"), "inputs/day5real.txt");
+        assert_eq!(name("./inputs/day5real.txt"), "inputs/day5real.txt");
+        assert_eq!(
+            name("inputs/./../inputs/day5real.txt"),
+            "inputs/day5real.txt"
+        );
+        assert_eq!(name("../day5real.txt"), "../day5real.txt");
+
+        let here = std::env::current_dir().unwrap();
+        let absolute = here.join("inputs/day5real.txt");
+        assert_eq!(name(absolute.to_str().unwrap()), "inputs/day5real.txt");
+        assert_eq!(InputSource::Stdin.name(5), "-");
+    }
+}
@@ -0,0 +1,27 @@
+pub mod answers;
+pub mod bench;
+pub mod config;
+pub mod crosscheck;
+pub mod error;
+pub mod generate;
+pub mod input;
+pub mod runner;
+pub mod site;
+pub mod solver;
+pub mod submit;
+pub mod util;
+
+solver::register_days! {
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12,
+}
@@ -0,0 +1,46 @@
+use log::LevelFilter;
+
+/// How much the solvers log. Warnings are shown by default, `-v` shows the
+/// debug dumps and `-vv` the traces.
+pub fn level_for_verbosity(verbosity: i8) -> LevelFilter {
+    match verbosity {
+        i8::MIN..=-1 => LevelFilter::Error,
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Parse a per-day filter, like `5`, `day5` or `day5=debug`. Without a
+/// level, everything the day logs is shown.
+pub fn parse_day_filter(value: &str) -> Result<(u8, LevelFilter), String> {
+    let (day, level) = match value.split_once('=') {
+        Some((day, level)) => (day, level),
+        None => (value, "trace"),
+    };
+
+    let day = day
+        .trim_start_matches("day")
+        .parse()
+        .map_err(|_| format!("Invalid day in log filter: {}", value))?;
+    let level = level
+        .parse()
+        .map_err(|_| format!("Invalid level in log filter: {}", value))?;
+
+    Ok((day, level))
+}
+
+/// Start the logger. `RUST_LOG` still works, and wins over the flags.
+pub fn init(verbosity: i8, day_filters: &[(u8, LevelFilter)]) {
+    let mut builder = env_logger::Builder::new();
+    builder
+        .filter_level(level_for_verbosity(verbosity))
+        .format_timestamp(None);
+
+    for (day, level) in day_filters {
+        let module = format!("{}::day{}", env!("CARGO_CRATE_NAME"), day);
+        builder.filter_module(&module, *level);
+    }
+
+    builder.parse_default_env().init();
+}
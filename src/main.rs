@@ -1,49 +1,171 @@
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-pub mod util;
-
-use day1::day1;
-use day10::day10;
-use day11::day11;
-use day12::day12;
-use day2::day2;
-use day3::day3;
-use day4::day4;
-use day5::day5;
-use day6::day6;
-use day7::day7;
-use day8::{day8, day8view};
-use day9::day9;
+mod cli;
+mod logging;
+mod scaffold;
+#[cfg(target_os = "linux")]
+mod watch;
+
+use aoc2023::answers::verify;
+use aoc2023::config::{Config, CONFIG_FILE};
+use aoc2023::generate::{generate, random_seed};
+use aoc2023::runner::{check_input, run_all, run_day};
+use aoc2023::site::{fetch_input, load_session, Fetched, Site};
+use aoc2023::solver::DynSolver;
+use aoc2023::submit::submit;
+use aoc2023::util::input_path;
+use aoc2023::{bench, crosscheck, day8, registry};
+use cli::Command;
+use std::path::Path;
+
+fn list_days(solvers: &[Box<dyn DynSolver>]) {
+    for s in solvers {
+        println!("day{:<3} {}", s.day(), s.name());
+    }
+}
+
+fn find_solver<'a>(solvers: &'a [Box<dyn DynSolver>], day: &str) -> &'a dyn DynSolver {
+    let number = day.trim_start_matches("day").parse::<u8>().ok();
+
+    match solvers.iter().find(|s| Some(s.day()) == number) {
+        Some(solver) => solver.as_ref(),
+        None => {
+            eprintln!(
+                "Day not found: {}. Use `list` to see the available days",
+                day
+            );
+            std::process::exit(1);
+        }
+    }
+}
 
 fn main() {
-    let day = std::env::args()
-        .nth(1)
-        .expect("You need to pass the day (in the form of day<X>)");
-
-    match day.as_str() {
-        "day1" => day1(),
-        "day2" => day2(),
-        "day3" => day3(),
-        "day4" => day4(),
-        "day5" => day5(),
-        "day6" => day6(),
-        "day7" => day7(),
-        "day8" => day8(),
-        "day8view" => day8view(),
-        "day9" => day9(),
-        "day10" => day10(),
-        "day11" => day11(),
-        "day12" => day12(),
-        _ => panic!("Day not found!"),
+    let config = match Config::load(CONFIG_FILE) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let args = match cli::parse_args(std::env::args().skip(1), &config) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(1);
+        }
+    };
+
+    logging::init(args.verbosity, &args.log_filters);
+    let mut solvers = registry();
+    if let Err(e) = config.configure(&mut solvers) {
+        eprintln!("{}: {}", CONFIG_FILE, e);
+        std::process::exit(1);
+    }
+
+    match args.command {
+        Command::List => list_days(&solvers),
+        Command::All => run_all(&solvers, &args.input, &args.parts, args.format, args.jobs),
+        Command::Verify => {
+            if !verify(&solvers, &args.answers) {
+                std::process::exit(1);
+            }
+        }
+        Command::Day8View => {
+            let lines = args.input.open(8).and_then(|i| i.lines());
+            let lines = lines.unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            if let Err(e) = day8::day8view(lines.into_iter()) {
+                eprintln!("Invalid input: {}", e.for_day(8));
+                std::process::exit(1);
+            }
+        }
+        Command::Bench(ref day) => {
+            let solver = find_solver(&solvers, day);
+            let (input, parts, strategies) = (&args.input, &args.parts, &args.strategies);
+            if let Err(e) = bench::report(solver, input, parts, strategies, args.runs, args.warmup)
+            {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Command::New(day) => {
+            let name = args.name.clone().unwrap_or_else(|| format!("Day {}", day));
+            // the same directory where aoc.toml and the inputs are
+            if let Err(e) = scaffold::new_day(Path::new("."), &config.inputs, day, &name) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(target_os = "linux")]
+        Command::Watch(day) => {
+            // the runs read aoc.toml from the same directory as this one
+            let root = Path::new(".");
+            if let Err(e) = watch::watch(root, Path::new(&config.inputs), day) {
+                eprintln!("Could not watch the files: {}", e);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        Command::Watch(_) => {
+            eprintln!("`watch` only works on Linux");
+            std::process::exit(1);
+        }
+        Command::Fetch(day) => {
+            let path = input_path(&config.inputs, day, "real");
+            let site = || Ok(Site::new(&args.url, &load_session()?));
+
+            match fetch_input(site, day, Path::new(&path)) {
+                Ok(Fetched::Cached) => println!("{} is already there", path),
+                Ok(Fetched::Downloaded) => println!("downloaded {}", path),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Submit(ref day, part) => {
+            let solver = find_solver(&solvers, day);
+            let (answers, submissions) = (&args.answers, &args.submissions);
+            if let Err(e) = submit(solver, part, &args.input, answers, submissions, &args.url) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Command::Check(ref day) => {
+            if !check_input(find_solver(&solvers, day), &args.input) {
+                std::process::exit(1);
+            }
+        }
+        Command::Generate(day) => {
+            // the seed goes to stderr, so the input can be made again
+            let seed = args.seed.unwrap_or_else(random_seed);
+            eprintln!("seed {}", seed);
+
+            match generate(day, args.size, seed) {
+                Some(lines) => lines.iter().for_each(|l| println!("{}", l)),
+                None => {
+                    eprintln!("There is no generator for day {}", day);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Crosscheck(ref day) => {
+            let chosen: Vec<&dyn DynSolver> = match day {
+                Some(day) => vec![find_solver(&solvers, day)],
+                None => solvers.iter().map(|s| s.as_ref()).collect(),
+            };
+            let seed = args.seed.unwrap_or_else(random_seed);
+            let max_size = args.size.unwrap_or(3);
+            if !crosscheck::report(&chosen, args.runs, max_size, seed) {
+                std::process::exit(1);
+            }
+        }
+        Command::Run(ref day) => {
+            let solver = find_solver(&solvers, day);
+            if !run_day(solver, &args.input, &args.parts, args.format) {
+                std::process::exit(1);
+            }
+        }
     }
 }
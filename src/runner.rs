@@ -0,0 +1,371 @@
+use crate::error::ParseError;
+use crate::input::InputSource;
+use crate::solver::{Answer, DynSolver};
+use serde_json::{json, Value};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Answer(Answer),
+    NotSolved,
+    MissingInput,
+    InvalidInput(ParseError),
+    Panicked,
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: u8,
+    pub outcome: Outcome,
+    pub solve_time: Duration,
+}
+
+/// Result of parsing the input of a day once and solving the selected parts
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub name: &'static str,
+    pub parse_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+impl DayRun {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.parts.iter().map(|p| p.solve_time).sum::<Duration>()
+    }
+
+    /// Times are in milliseconds. Parts without an answer have a `null`
+    /// answer and a status saying why.
+    pub fn to_json(&self) -> Value {
+        let parts: Vec<Value> = self
+            .parts
+            .iter()
+            .map(|p| {
+                let (status, answer) = match &p.outcome {
+                    Outcome::Answer(answer) => ("solved", answer.to_json()),
+                    Outcome::NotSolved => ("not-solved", Value::Null),
+                    Outcome::MissingInput => ("missing-input", Value::Null),
+                    Outcome::InvalidInput(_) => ("invalid-input", Value::Null),
+                    Outcome::Panicked => ("panicked", Value::Null),
+                };
+
+                json!({
+                    "part": p.part,
+                    "status": status,
+                    "answer": answer,
+                    "solve_time_ms": millis(p.solve_time),
+                })
+            })
+            .collect();
+
+        let error = match self.parts.first().map(|p| &p.outcome) {
+            Some(Outcome::InvalidInput(e)) => json!(e.to_string()),
+            _ => Value::Null,
+        };
+
+        json!({
+            "day": self.day,
+            "name": self.name,
+            "parse_time_ms": millis(self.parse_time),
+            "error": error,
+            "parts": parts,
+        })
+    }
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Option<T>, Duration) {
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).ok();
+    (result, start.elapsed())
+}
+
+/// Parse and solve a day, measuring the parse and each solve separately.
+///
+/// A panic inside a solver is caught, so one broken day does not stop the
+/// others. When there is no input, nothing runs.
+pub fn run_timed(
+    solver: &dyn DynSolver,
+    lines: Option<&mut dyn Iterator<Item = String>>,
+    parts: &[u8],
+) -> DayRun {
+    let skipped = |outcome: Outcome| {
+        parts
+            .iter()
+            .map(|&part| PartRun {
+                part,
+                outcome: outcome.clone(),
+                solve_time: Duration::ZERO,
+            })
+            .collect()
+    };
+
+    let (input, parse_time) = match lines {
+        Some(lines) => timed(|| solver.parse(lines)),
+        None => {
+            return DayRun {
+                day: solver.day(),
+                name: solver.name(),
+                parse_time: Duration::ZERO,
+                parts: skipped(Outcome::MissingInput),
+            }
+        }
+    };
+
+    let parts = match input {
+        None => skipped(Outcome::Panicked),
+        Some(Err(e)) => skipped(Outcome::InvalidInput(e)),
+        Some(Ok(input)) => parts
+            .iter()
+            .map(|&part| {
+                let (answer, solve_time) = timed(|| match part {
+                    1 => solver.part1(&*input),
+                    _ => solver.part2(&*input),
+                });
+
+                let outcome = match answer {
+                    Some(Some(answer)) => Outcome::Answer(answer),
+                    Some(None) => Outcome::NotSolved,
+                    None => Outcome::Panicked,
+                };
+
+                PartRun {
+                    part,
+                    outcome,
+                    solve_time,
+                }
+            })
+            .collect(),
+    };
+
+    DayRun {
+        day: solver.day(),
+        name: solver.name(),
+        parse_time,
+        parts,
+    }
+}
+
+/// Read an input and run a day on it. An input that cannot be read is
+/// reported, and the parts are left without an answer.
+pub fn run_input(solver: &dyn DynSolver, input: &InputSource, parts: &[u8]) -> DayRun {
+    if !input.is_available(solver.day()) {
+        return run_timed(solver, None, parts);
+    }
+
+    match input.open(solver.day()).and_then(|input| input.lines()) {
+        Ok(lines) => run_timed(solver, Some(&mut lines.into_iter()), parts),
+        Err(e) => {
+            eprintln!("{}", e);
+            run_timed(solver, None, parts)
+        }
+    }
+}
+
+/// Call `run` on every item, on up to `jobs` threads, and return the results
+/// in the order of the items. Each thread takes the next item when it is done
+/// with the last one, so a slow day does not hold back the others.
+pub fn run_in_parallel<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    run: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let worker = || {
+        let mut results = vec![];
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            match items.get(index) {
+                Some(item) => results.push((index, run(item))),
+                None => return results,
+            }
+        }
+    };
+
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, items.len().max(1)))
+            .map(|_| scope.spawn(worker))
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap())
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+pub fn format_outcome(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Answer(answer) => answer.to_string(),
+        Outcome::NotSolved => String::from("(not solved yet)"),
+        Outcome::MissingInput => String::from("(missing input)"),
+        Outcome::InvalidInput(_) => String::from("(invalid input)"),
+        Outcome::Panicked => String::from("(panicked)"),
+    }
+}
+
+/// `elapsed` is the wall time of the whole run, which is less than the sum
+/// of the days when they run in parallel
+pub fn print_summary_table(runs: &[DayRun], elapsed: Duration) {
+    println!(
+        "{:>3}  {:<32}  {:>4}  {:<20}  {:>12}  {:>12}",
+        "Day", "Name", "Part", "Answer", "Parse", "Solve"
+    );
+
+    for run in runs {
+        for part in &run.parts {
+            println!(
+                "{:>3}  {:<32}  {:>4}  {:<20}  {:>12}  {:>12}",
+                run.day,
+                run.name,
+                part.part,
+                format_outcome(&part.outcome),
+                format!("{:.2?}", run.parse_time),
+                format!("{:.2?}", part.solve_time),
+            );
+        }
+    }
+
+    let total: Duration = runs.iter().map(|r| r.total_time()).sum();
+    println!("\nTotal: {:.2?} ({:.2?} elapsed)", total, elapsed);
+
+    // the parse error is the same for both parts
+    for run in runs {
+        if let Some(Outcome::InvalidInput(e)) = run.parts.first().map(|p| &p.outcome) {
+            println!("{}", e);
+        }
+    }
+}
+
+pub fn print_summary_json(runs: &[DayRun], elapsed: Duration) {
+    let total: Duration = runs.iter().map(|r| r.total_time()).sum();
+    let summary = json!({
+        "days": runs.iter().map(|r| r.to_json()).collect::<Vec<_>>(),
+        "total_time_ms": millis(total),
+        "elapsed_ms": millis(elapsed),
+    });
+
+    println!("{}", summary);
+}
+
+/// Run a day and print its answers. Returns if every part had an answer or
+/// was not solved yet.
+pub fn run_day(solver: &dyn DynSolver, input: &InputSource, parts: &[u8], format: Format) -> bool {
+    let run = run_input(solver, input, parts);
+
+    if format == Format::Json {
+        println!("{}", run.to_json());
+    }
+
+    let mut ok = true;
+    for part in &run.parts {
+        match &part.outcome {
+            Outcome::Answer(answer) if format == Format::Text => println!("{}", answer),
+            Outcome::Answer(_) => {}
+            Outcome::NotSolved => eprintln!("part {}: not solved yet", part.part),
+            Outcome::MissingInput => {
+                eprintln!("Input not found for day {}", run.day);
+                return false;
+            }
+            Outcome::InvalidInput(e) => {
+                eprintln!("Invalid input: {}", e);
+                return false;
+            }
+            Outcome::Panicked => {
+                eprintln!("part {}: panicked", part.part);
+                ok = false;
+            }
+        }
+    }
+
+    ok
+}
+
+/// Run every day, on up to `jobs` threads, and print a summary in day order
+pub fn run_all(
+    solvers: &[Box<dyn DynSolver>],
+    input: &InputSource,
+    parts: &[u8],
+    format: Format,
+    jobs: usize,
+) {
+    let start = Instant::now();
+    let runs = run_in_parallel(solvers, jobs, |solver| {
+        run_input(solver.as_ref(), input, parts)
+    });
+    let elapsed = start.elapsed();
+
+    match format {
+        Format::Text => print_summary_table(&runs, elapsed),
+        Format::Json => print_summary_json(&runs, elapsed),
+    }
+}
+
+/// Parse an input without solving it, and say what is wrong with it.
+/// Returns if the input is valid.
+pub fn check_input(solver: &dyn DynSolver, input: &InputSource) -> bool {
+    let name = input.name(solver.day());
+    let lines = match input.open(solver.day()).and_then(|input| input.lines()) {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("{}", e);
+            return false;
+        }
+    };
+
+    match solver.parse(&mut lines.into_iter()) {
+        Ok(_) => {
+            println!("{}: ok", name);
+            true
+        }
+        Err(e) => {
+            println!("{}: {}", name, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_results_keep_the_order() {
+        let items: Vec<u64> = (0..20).collect();
+
+        // the first items are the slowest, so they finish last
+        let results = run_in_parallel(&items, 4, |&i| {
+            thread::sleep(Duration::from_millis(20 - i));
+            i * 2
+        });
+
+        assert_eq!(results, (0..20).map(|i| i * 2).collect::<Vec<_>>());
+        assert!(run_in_parallel(&items[..0], 4, |&i| i).is_empty());
+    }
+
+    #[test]
+    fn missing_inputs_are_not_run() {
+        let solvers = crate::registry();
+        let input = InputSource::File(String::from("inputs/day99nothing.txt"));
+        let run = run_input(solvers[0].as_ref(), &input, &[1, 2]);
+
+        assert_eq!(run.parts.len(), 2);
+        assert!(run.parts.iter().all(|p| p.outcome == Outcome::MissingInput));
+        assert!(!check_input(solvers[0].as_ref(), &input));
+    }
+}
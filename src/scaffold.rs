@@ -0,0 +1,178 @@
+use aoc2023::util::input_path;
+use std::fs;
+use std::path::Path;
+
+const TEMPLATE: &str = r#"// https://adventofcode.com/2023/day/{DAY}
+
+use crate::error::{parse_each_line, ParseError};
+use crate::solver::{Answer, Solver};
+
+#[derive(Default)]
+pub struct Day{DAY};
+
+impl Solver for Day{DAY} {
+    type Input = Vec<String>;
+
+    fn day(&self) -> u8 {
+        {DAY}
+    }
+
+    fn name(&self) -> &'static str {
+        "{NAME}"
+    }
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError> {
+        parse_each_line(lines, |line| Ok(line.to_string()))
+    }
+
+    fn part1(&self, _input: &Self::Input) -> Option<Answer> {
+        None
+    }
+
+    fn part2(&self, _input: &Self::Input) -> Option<Answer> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_example, Answer};
+
+    const EXAMPLE: &str = include_str!("{EXAMPLE_FROM_SRC}");
+
+    #[test]
+    #[ignore = "add the example to {EXAMPLE} and its answer here"]
+    fn part1_example() {
+        assert_eq!(solve_example(&Day{DAY}, EXAMPLE, 1), Some(Answer::Int(0)));
+    }
+
+    #[test]
+    #[ignore = "add the example to {EXAMPLE} and its answer here"]
+    fn part2_example() {
+        assert_eq!(solve_example(&Day{DAY}, EXAMPLE, 2), Some(Answer::Int(0)));
+    }
+}
+"#;
+
+/// Path of the example input of a day, relative to `src/` like
+/// `include_str!` needs it, when the inputs are in `inputs`
+fn example_from_src(inputs: &str, day: u8) -> String {
+    let example = input_path(inputs, day, "test");
+    match Path::new(inputs).is_absolute() {
+        true => example,
+        false => format!("../{}", example.trim_start_matches("./")),
+    }
+}
+
+const REGISTRY_START: &str = "register_days! {";
+
+/// Add `dayN::DayN` to the `register_days!` list in the source of lib.rs,
+/// keeping the list sorted by day
+fn register_day(lib: &str, day: u8) -> Result<String, String> {
+    let start = lib
+        .find(REGISTRY_START)
+        .map(|i| i + REGISTRY_START.len())
+        .ok_or("register_days! not found in lib.rs")?;
+    let end = start
+        + lib[start..]
+            .find('}')
+            .ok_or("end of register_days! not found in lib.rs")?;
+
+    let mut days: Vec<u8> = lib[start..end]
+        .split(',')
+        .filter_map(|entry| entry.trim().split("::").next())
+        .filter_map(|module| module.strip_prefix("day")?.parse().ok())
+        .collect();
+
+    if days.contains(&day) {
+        return Err(format!("Day {} is already registered", day));
+    }
+    days.push(day);
+    days.sort();
+
+    let entries: String = days
+        .iter()
+        .map(|d| format!("    day{}::Day{},\n", d, d))
+        .collect();
+
+    Ok(format!("{}\n{}{}", &lib[..start], entries, &lib[end..]))
+}
+
+/// Create `src/dayN.rs` and empty input files for a new day, and register
+/// the day. Nothing is overwritten.
+pub fn new_day(root: &Path, inputs: &str, day: u8, name: &str) -> Result<(), String> {
+    let module = root.join(format!("src/day{}.rs", day));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()));
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let lib = fs::read_to_string(&lib_path).map_err(|e| {
+        format!(
+            "Could not read {} (`new` runs from the root of the repository): {}",
+            lib_path.display(),
+            e
+        )
+    })?;
+    let lib = register_day(&lib, day)?;
+
+    let source = TEMPLATE
+        .replace("{EXAMPLE_FROM_SRC}", &example_from_src(inputs, day))
+        .replace(
+            "{EXAMPLE}",
+            input_path(inputs, day, "test").trim_start_matches("./"),
+        )
+        .replace("{DAY}", &day.to_string())
+        .replace("{NAME}", &name.replace('"', "\\\""));
+    fs::write(&module, source).map_err(|e| e.to_string())?;
+    fs::write(&lib_path, lib).map_err(|e| e.to_string())?;
+    println!("created {}", module.display());
+
+    for variant in ["test", "real"] {
+        let input = root.join(input_path(inputs, day, variant).trim_start_matches("./"));
+        if !input.exists() {
+            fs::create_dir_all(root.join(inputs)).map_err(|e| e.to_string())?;
+            fs::write(&input, "").map_err(|e| e.to_string())?;
+            println!("created {}", input.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod util;\n\nsolver::register_days! {\n    day1::Day1,\n    day10::Day10,\n    day2::Day2,\n}\n";
+
+    #[test]
+    fn days_are_registered_in_order() {
+        let lib = register_day(LIB, 3).unwrap();
+
+        assert_eq!(
+            lib,
+            "pub mod util;\n\nsolver::register_days! {\n    day1::Day1,\n    day2::Day2,\n    day3::Day3,\n    day10::Day10,\n}\n"
+        );
+    }
+
+    #[test]
+    fn examples_are_included_from_the_inputs_directory() {
+        assert_eq!(example_from_src("./inputs", 3), "../inputs/day3test.txt");
+        assert_eq!(example_from_src("data/aoc/", 3), "../data/aoc/day3test.txt");
+        assert_eq!(
+            example_from_src("../inputs", 3),
+            "../../inputs/day3test.txt"
+        );
+        assert_eq!(
+            example_from_src("/srv/inputs", 3),
+            "/srv/inputs/day3test.txt"
+        );
+    }
+
+    #[test]
+    fn registered_days_are_not_added_again() {
+        assert!(register_day(LIB, 10).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub const YEAR: u16 = 2023;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable with the session token, which is the `session`
+/// cookie of a logged in browser
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// File with the session token, used when the variable is not set
+pub const SESSION_FILE: &str = ".aoc-session";
+
+const USER_AGENT: &str = "github.com/arthurmco/aoc2023 by arthurmco";
+
+/// The Advent of Code site, or anything that answers like it
+pub struct Site {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Site {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Site {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(USER_AGENT)
+                .build(),
+        }
+    }
+
+    pub fn day_url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    fn get(&self, url: &str) -> Result<String, String> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+
+        Site::read_response("download", url, response)
+    }
+
+    fn post(&self, url: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let response = self
+            .agent
+            .post(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(form);
+
+        Site::read_response("send the answer to", url, response)
+    }
+
+    /// `action` is what the request was for, like `download`, for the errors
+    fn read_response(
+        action: &str,
+        url: &str,
+        response: Result<ureq::Response, ureq::Error>,
+    ) -> Result<String, String> {
+        match response {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| format!("Could not {} {}: {}", action, url, e)),
+            Err(ureq::Error::Status(code, _)) => Err(match code {
+                400 | 401 | 500 => {
+                    format!("{} returned {}, is the session token right?", url, code)
+                }
+                404 => format!("{} was not found, is the puzzle unlocked yet?", url),
+                code => format!("{} returned {}", url, code),
+            }),
+            Err(e) => Err(format!("Could not {} {}: {}", action, url, e)),
+        }
+    }
+
+    pub fn download_input(&self, day: u8) -> Result<String, String> {
+        self.get(&format!("{}/input", self.day_url(day)))
+    }
+
+    pub fn submit_answer(&self, day: u8, part: u8, answer: &str) -> Result<Response, String> {
+        let url = format!("{}/answer", self.day_url(day));
+        let page = self.post(&url, &[("level", &part.to_string()), ("answer", answer)])?;
+
+        Response::from_page(&page)
+    }
+}
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Response {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// An answer was sent too recently
+    Wait(Duration),
+    /// The part was already solved, so the answer was not checked
+    AlreadySolved,
+}
+
+impl Response {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Response::Right => "right",
+            Response::Wrong => "wrong",
+            Response::TooHigh => "too-high",
+            Response::TooLow => "too-low",
+            Response::Wait(_) => "wait",
+            Response::AlreadySolved => "already-solved",
+        }
+    }
+
+    /// Find the response in the page returned after posting an answer
+    pub fn from_page(page: &str) -> Result<Response, String> {
+        if page.contains("That's the right answer") {
+            Ok(Response::Right)
+        } else if page.contains("That's not the right answer") {
+            Ok(match page {
+                p if p.contains("your answer is too high") => Response::TooHigh,
+                p if p.contains("your answer is too low") => Response::TooLow,
+                _ => Response::Wrong,
+            })
+        } else if page.contains("You gave an answer too recently") {
+            let wait = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+            let seconds = match wait.captures(page) {
+                Some(caps) => {
+                    let number = |i| caps.get(i).map_or(0, |m| m.as_str().parse().unwrap_or(0));
+                    number(1) * 60 + number(2)
+                }
+                // not worth guessing, the usual wait is a minute
+                None => 60,
+            };
+
+            Ok(Response::Wait(Duration::from_secs(seconds)))
+        } else if page.contains("Did you already complete it?") {
+            Ok(Response::AlreadySolved)
+        } else {
+            Err(String::from("Unknown response from the site"))
+        }
+    }
+}
+
+/// Read the session token from `AOC_SESSION`, or from `.aoc-session`
+pub fn load_session() -> Result<String, String> {
+    if let Ok(session) = std::env::var(SESSION_ENV) {
+        return Ok(session);
+    }
+
+    fs::read_to_string(SESSION_FILE).map_err(|_| {
+        format!(
+            "No session token: set {} or write it to {}",
+            SESSION_ENV, SESSION_FILE
+        )
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+/// Download the input of a day to `path`, unless it is already there. The
+/// session is only loaded when the input needs to be downloaded.
+pub fn fetch_input(
+    site: impl FnOnce() -> Result<Site, String>,
+    day: u8,
+    path: &Path,
+) -> Result<Fetched, String> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+
+    let input = site()?.download_input(day)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, input).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+
+    Ok(Fetched::Downloaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::thread;
+
+    /// Server that answers `responses` requests, in order, with the given
+    /// status and body. Returns its URL and the requests it received.
+    fn stand_in_server(
+        responses: Vec<(u16, &'static str)>,
+    ) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request += &line;
+                }
+
+                let mut form = vec![0; length];
+                reader.read_exact(&mut form).unwrap();
+                request += &String::from_utf8(form).unwrap();
+                requests.push(request);
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2023-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("inputs/day3real.txt")
+    }
+
+    #[test]
+    fn downloads_once_and_then_uses_the_cache() {
+        let (url, server) = stand_in_server(vec![(200, "467..114..\n")]);
+        let path = temp_path("cache");
+
+        let site = || Ok(Site::new(&url, "token"));
+        assert_eq!(fetch_input(site, 3, &path), Ok(Fetched::Downloaded));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "467..114..\n");
+
+        // the server would not answer a second time
+        let no_site = || Err(String::from("should not be called"));
+        assert_eq!(fetch_input(no_site, 3, &path), Ok(Fetched::Cached));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2023/day/3/input "));
+        assert!(requests[0].contains("session=token"));
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let (url, server) = stand_in_server(vec![(404, "Not found"), (400, "")]);
+        let path = temp_path("errors");
+
+        let site = || Ok(Site::new(&url, "token"));
+        assert!(fetch_input(site, 3, &path)
+            .unwrap_err()
+            .contains("unlocked"));
+        assert!(fetch_input(site, 3, &path).unwrap_err().contains("session"));
+        assert!(!path.exists());
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn answers_are_posted_with_the_part() {
+        let page =
+            "<article><p>That's the right answer!  You are one gold star closer.</p></article>";
+        let (url, server) = stand_in_server(vec![(200, page)]);
+
+        let site = Site::new(&url, "token");
+        assert_eq!(site.submit_answer(5, 2, "46"), Ok(Response::Right));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2023/day/5/answer "));
+        assert!(requests[0].contains("session=token"));
+        assert!(requests[0].ends_with("level=2&answer=46"));
+    }
+
+    #[test]
+    fn responses_from_the_page() {
+        let wrong = "<p>That's not the right answer.  If you're stuck, make sure you're using the full input data</p>";
+        let high =
+            "<p>That's not the right answer; your answer is too high.  If you're stuck...</p>";
+        let low = "<p>That's not the right answer; your answer is too low.</p>";
+        let wait = "<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 56s left to wait.</p>";
+        let short_wait = "<p>You gave an answer too recently.  You have 41s left to wait.</p>";
+        let solved =
+            "<p>You don't seem to be solving the right level.  Did you already complete it?</p>";
+
+        assert_eq!(Response::from_page(wrong), Ok(Response::Wrong));
+        assert_eq!(Response::from_page(high), Ok(Response::TooHigh));
+        assert_eq!(Response::from_page(low), Ok(Response::TooLow));
+        assert_eq!(
+            Response::from_page(wait),
+            Ok(Response::Wait(Duration::from_secs(296)))
+        );
+        assert_eq!(
+            Response::from_page(short_wait),
+            Ok(Response::Wait(Duration::from_secs(41)))
+        );
+        assert_eq!(Response::from_page(solved), Ok(Response::AlreadySolved));
+        assert!(Response::from_page("<html></html>").is_err());
+    }
+
+    #[test]
+    fn errors_say_what_failed() {
+        // nothing listens on the port once the listener is gone
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let site = Site::new(&url, "token");
+        let e = site.download_input(5).unwrap_err();
+        assert!(e.starts_with(&format!("Could not download {}/2023/day/5/input: ", url)));
+        let e = site.submit_answer(5, 1, "35").unwrap_err();
+        assert!(e.starts_with(&format!(
+            "Could not send the answer to {}/2023/day/5/answer: ",
+            url
+        )));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::error::ParseError;
+use num::BigInt;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::fmt;
+
+/// Answer of a part. Integers that do not fit in an `i64` become a
+/// `BigInt`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Int(i64),
+    BigInt(BigInt),
+    Str(String),
+}
+
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                match i64::try_from(value) {
+                    Ok(value) => Answer::Int(value),
+                    Err(_) => Answer::BigInt(BigInt::from(value)),
+                }
+            }
+        })*
+    };
+}
+
+answer_from_integer!(u32, u64, u128, usize, i32, i128, isize);
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Int(value)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        match i64::try_from(&value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::BigInt(value),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Str(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Str(value.to_string())
+    }
+}
+
+impl Answer {
+    /// Big integers go as strings, because most JSON readers would round
+    /// them
+    pub fn to_json(&self) -> Value {
+        match self {
+            Answer::Int(value) => json!(value),
+            Answer::BigInt(value) => json!(value.to_string()),
+            Answer::Str(value) => json!(value),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::BigInt(value) => write!(f, "{}", value),
+            Answer::Str(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Another way of solving a part, with a name to choose it from `bench`
+pub type Strategy<I> = (&'static str, fn(&I) -> Option<Answer>);
+
+/// A solution for a single day of the calendar.
+///
+/// `parse` turns the puzzle input into the model of the day, and each part
+/// is solved from that model. A part that was not solved yet returns `None`.
+pub trait Solver {
+    type Input: 'static;
+
+    fn day(&self) -> u8;
+
+    fn name(&self) -> &'static str;
+
+    fn parse(&self, lines: impl Iterator<Item = String>) -> Result<Self::Input, ParseError>;
+
+    fn part1(&self, _input: &Self::Input) -> Option<Answer> {
+        None
+    }
+
+    fn part2(&self, _input: &Self::Input) -> Option<Answer> {
+        None
+    }
+
+    /// Alternative implementations of a part, besides `part1` and `part2`,
+    /// so they can be compared
+    fn strategies(&self, _part: u8) -> Vec<Strategy<Self::Input>> {
+        vec![]
+    }
+
+    /// Set the parameters of the day from its table in the config file. Days
+    /// without parameters accept only an empty table.
+    fn configure(&mut self, params: &toml::Table) -> Result<(), String> {
+        match params.keys().next() {
+            Some(key) => Err(format!("Unknown parameter: {}", key)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Object-safe version of [`Solver`], so every day can live in the same
+/// registry. The parsed model travels around as a `dyn Any`.
+pub trait DynSolver: Send + Sync {
+    fn day(&self) -> u8;
+
+    fn name(&self) -> &'static str;
+
+    fn parse(&self, lines: &mut dyn Iterator<Item = String>) -> Result<Box<dyn Any>, ParseError>;
+
+    fn part1(&self, input: &dyn Any) -> Option<Answer>;
+
+    fn part2(&self, input: &dyn Any) -> Option<Answer>;
+
+    /// Names of the ways a part can be solved. The first one is always
+    /// `default`, which is `part1` or `part2`.
+    fn strategies(&self, part: u8) -> Vec<&'static str>;
+
+    /// Solve a part with one of its `strategies`. Panics if there is no
+    /// strategy with that name.
+    fn solve_with(&self, part: u8, strategy: &str, input: &dyn Any) -> Option<Answer>;
+
+    fn configure(&mut self, params: &toml::Table) -> Result<(), String>;
+}
+
+impl<S: Solver + Send + Sync> DynSolver for S {
+    fn day(&self) -> u8 {
+        Solver::day(self)
+    }
+
+    fn name(&self) -> &'static str {
+        Solver::name(self)
+    }
+
+    fn parse(&self, lines: &mut dyn Iterator<Item = String>) -> Result<Box<dyn Any>, ParseError> {
+        match Solver::parse(self, lines) {
+            Ok(input) => Ok(Box::new(input)),
+            Err(e) => Err(e.for_day(Solver::day(self))),
+        }
+    }
+
+    fn part1(&self, input: &dyn Any) -> Option<Answer> {
+        Solver::part1(self, downcast_input::<S>(input))
+    }
+
+    fn part2(&self, input: &dyn Any) -> Option<Answer> {
+        Solver::part2(self, downcast_input::<S>(input))
+    }
+
+    fn strategies(&self, part: u8) -> Vec<&'static str> {
+        let others = Solver::strategies(self, part).into_iter().map(|(n, _)| n);
+        std::iter::once(DEFAULT_STRATEGY).chain(others).collect()
+    }
+
+    fn solve_with(&self, part: u8, strategy: &str, input: &dyn Any) -> Option<Answer> {
+        match (strategy, part) {
+            (DEFAULT_STRATEGY, 1) => DynSolver::part1(self, input),
+            (DEFAULT_STRATEGY, _) => DynSolver::part2(self, input),
+            _ => {
+                let (_, solve) = Solver::strategies(self, part)
+                    .into_iter()
+                    .find(|(name, _)| *name == strategy)
+                    .unwrap_or_else(|| panic!("no strategy {} for part {}", strategy, part));
+
+                solve(downcast_input::<S>(input))
+            }
+        }
+    }
+
+    fn configure(&mut self, params: &toml::Table) -> Result<(), String> {
+        Solver::configure(self, params)
+    }
+}
+
+/// `Solver::configure` for days that derive `Deserialize`, with
+/// `#[serde(default)]` so the parameters missing from the table keep their
+/// default values
+pub fn configure_from<P: DeserializeOwned>(
+    day: &mut P,
+    params: &toml::Table,
+) -> Result<(), String> {
+    *day = toml::Value::Table(params.clone())
+        .try_into()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    Ok(())
+}
+
+pub const DEFAULT_STRATEGY: &str = "default";
+
+fn downcast_input<S: Solver>(input: &dyn Any) -> &S::Input {
+    input
+        .downcast_ref::<S::Input>()
+        .expect("input was parsed by a different solver")
+}
+
+/// Declares the day modules and generates `registry()`, which returns one
+/// instance of each solver sorted by day.
+///
+/// A new day only needs to be added to the list, in the form
+/// `module::SolverStruct`.
+macro_rules! register_days {
+    ($($module:ident::$solver:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        pub fn registry() -> Vec<Box<dyn $crate::solver::DynSolver>> {
+            let mut solvers: Vec<Box<dyn $crate::solver::DynSolver>> =
+                vec![$(Box::new($module::$solver::default())),*];
+            solvers.sort_by_key(|s| s.day());
+            solvers
+        }
+    };
+}
+
+pub(crate) use register_days;
+
+/// Parse an example input and solve one of its parts, for the tests of each
+/// day.
+#[cfg(test)]
+pub fn solve_example<S: Solver>(solver: &S, example: &str, part: u8) -> Option<Answer> {
+    let input = solver
+        .parse(example.lines().map(String::from))
+        .expect("the example should parse");
+
+    match part {
+        1 => solver.part1(&input),
+        _ => solver.part2(&input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_from_integers() {
+        assert_eq!(Answer::from(42usize), Answer::Int(42));
+        assert_eq!(Answer::from(-3isize), Answer::Int(-3));
+        assert_eq!(
+            Answer::from(u64::MAX),
+            Answer::BigInt(BigInt::from(u64::MAX))
+        );
+        assert_eq!(Answer::from(BigInt::from(7)), Answer::Int(7));
+    }
+
+    #[test]
+    fn big_answers_are_json_strings() {
+        assert_eq!(Answer::from(12u32).to_json(), json!(12));
+        assert_eq!(
+            Answer::from(u64::MAX).to_json(),
+            json!("18446744073709551615")
+        );
+        assert_eq!(Answer::from("abc").to_json(), json!("abc"));
+    }
+}
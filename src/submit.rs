@@ -0,0 +1,267 @@
+use crate::answers::{judge, parse_answers, AnswerStatus, KnownAnswer, Verdict};
+use crate::input::InputSource;
+use crate::runner::{run_input, Outcome};
+use crate::site::{load_session, Response, Site};
+use crate::solver::DynSolver;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_LOG: &str = "submissions.log";
+
+/// One answer sent to the site, and what it said
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    /// Unix time, in seconds
+    pub time: u64,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub response: Response,
+}
+
+impl Submission {
+    /// `<time> <day> <part> <answer> <response> [<seconds to wait>]`
+    pub fn to_line(&self) -> String {
+        let line = format!(
+            "{} {} {} {} {}",
+            self.time,
+            self.day,
+            self.part,
+            self.answer,
+            self.response.as_str()
+        );
+
+        match self.response {
+            Response::Wait(wait) => format!("{} {}", line, wait.as_secs()),
+            _ => line,
+        }
+    }
+
+    fn from_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let number = |i: usize| -> Result<u64, String> {
+            let field = fields.get(i).ok_or("missing field")?;
+            field
+                .parse()
+                .map_err(|_| format!("Invalid number: {}", field))
+        };
+
+        let response = match fields.get(4).copied() {
+            Some("right") => Response::Right,
+            Some("wrong") => Response::Wrong,
+            Some("too-high") => Response::TooHigh,
+            Some("too-low") => Response::TooLow,
+            Some("wait") => Response::Wait(Duration::from_secs(number(5)?)),
+            Some("already-solved") => Response::AlreadySolved,
+            Some(x) => return Err(format!("Invalid response: {}", x)),
+            None => return Err(String::from("missing response")),
+        };
+
+        Ok(Submission {
+            time: number(0)?,
+            day: number(1)? as u8,
+            part: number(2)? as u8,
+            answer: fields[3].to_string(),
+            response,
+        })
+    }
+}
+
+pub fn parse_log(text: &str) -> Result<Vec<Submission>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(index, l)| {
+            Submission::from_line(l).map_err(|e| format!("line {}: {}", index + 1, e))
+        })
+        .collect()
+}
+
+/// Append a line to a file, creating it if needed
+pub fn append_line(path: &str, line: &str) -> Result<(), String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| writeln!(f, "{}", line))
+        .map_err(|e| format!("Could not write to {}: {}", path, e))
+}
+
+/// Status to save in the answers file, when the site judged the answer
+pub fn status_of(response: Response) -> Option<AnswerStatus> {
+    match response {
+        Response::Right => Some(AnswerStatus::Right),
+        Response::Wrong => Some(AnswerStatus::Wrong),
+        Response::TooHigh => Some(AnswerStatus::TooHigh),
+        Response::TooLow => Some(AnswerStatus::TooLow),
+        Response::Wait(_) | Response::AlreadySolved => None,
+    }
+}
+
+/// Check if an answer is worth sending: it must not be known to be wrong,
+/// the part must not be solved yet, and the last wait must be over.
+/// `known` are the known answers of the same day, part and input.
+pub fn check_submission(
+    answer: &str,
+    known: &[&KnownAnswer],
+    log: &[Submission],
+    now: u64,
+) -> Result<(), String> {
+    if let Some(right) = known.iter().find(|k| k.status == AnswerStatus::Right) {
+        return Err(match right.answer == answer {
+            true => format!("{} was already accepted", answer),
+            false => format!("Already solved, with {}", right.answer),
+        });
+    }
+
+    if let Verdict::Fail(reason) = judge(answer, known) {
+        return Err(format!("Not sending {}: {}", answer, reason));
+    }
+
+    // the wait is for any answer, of any day
+    let wait_until = log
+        .iter()
+        .filter_map(|s| match s.response {
+            Response::Wait(wait) => Some(s.time + wait.as_secs()),
+            _ => None,
+        })
+        .max();
+    match wait_until {
+        Some(until) if until > now => Err(format!(
+            "An answer was sent too recently, wait {}s more",
+            until - now
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Read a file that may not exist yet, like the answers file or the log
+pub fn read_optional(path: &str) -> Result<String, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Could not read {}: {}", path, e)),
+    }
+}
+
+/// Solve a part and send the answer to the site at `url`, unless
+/// `check_submission` rules it out. The response goes to the `submissions`
+/// log, and to the `answers` file when the site judged the answer.
+pub fn submit(
+    solver: &dyn DynSolver,
+    part: u8,
+    input: &InputSource,
+    answers: &str,
+    submissions: &str,
+    url: &str,
+) -> Result<(), String> {
+    let day = solver.day();
+    let name = input.name(day);
+
+    let run = run_input(solver, input, &[part]);
+    let answer = match &run.parts[0].outcome {
+        Outcome::Answer(answer) => answer.to_string(),
+        Outcome::NotSolved => return Err(String::from("This part is not solved yet")),
+        Outcome::MissingInput => return Err(format!("Input not found: {}", name)),
+        Outcome::InvalidInput(e) => return Err(format!("Invalid input: {}", e)),
+        Outcome::Panicked => return Err(String::from("The solver panicked")),
+    };
+
+    let known = parse_answers(&read_optional(answers)?)?;
+    let known: Vec<&KnownAnswer> = known
+        .iter()
+        .filter(|k| k.day == day && k.part == part && k.input == name)
+        .collect();
+    let log = parse_log(&read_optional(submissions)?)
+        .map_err(|e| format!("Invalid {}: {}", submissions, e))?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    check_submission(&answer, &known, &log, now)?;
+
+    println!("sending {} for day {} part {}", answer, day, part);
+    let site = Site::new(url, &load_session()?);
+    let response = site.submit_answer(day, part, &answer)?;
+
+    let submission = Submission {
+        time: now,
+        day,
+        part,
+        answer: answer.clone(),
+        response,
+    };
+    append_line(submissions, &submission.to_line())?;
+
+    if let Some(status) = status_of(response) {
+        let known = KnownAnswer {
+            day,
+            part,
+            input: name,
+            answer,
+            status,
+        };
+        append_line(answers, &known.to_line())?;
+    }
+
+    match response {
+        Response::Wait(wait) => println!("too soon, wait {}s", wait.as_secs()),
+        Response::AlreadySolved => println!("this part was already solved"),
+        response => println!("{}", response.as_str()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::parse_answers;
+
+    const ANSWERS: &str =
+        "5 2 inputs/day5real.txt 198106515 too-high\n5 2 inputs/day5real.txt 1000 too-low\n";
+
+    #[test]
+    fn log_lines_round_trip() {
+        let submission = Submission {
+            time: 1701800000,
+            day: 5,
+            part: 2,
+            answer: String::from("26714516"),
+            response: Response::Wait(Duration::from_secs(42)),
+        };
+
+        let line = submission.to_line();
+        assert_eq!(line, "1701800000 5 2 26714516 wait 42");
+        assert_eq!(parse_log(&line), Ok(vec![submission]));
+    }
+
+    #[test]
+    fn known_wrong_answers_are_not_sent() {
+        let known = parse_answers(ANSWERS).unwrap();
+        let known: Vec<&KnownAnswer> = known.iter().collect();
+
+        assert!(check_submission("198106515", &known, &[], 0).is_err());
+        assert!(check_submission("300000000", &known, &[], 0).is_err());
+        assert!(check_submission("999", &known, &[], 0).is_err());
+        assert!(check_submission("26714516", &known, &[], 0).is_ok());
+    }
+
+    #[test]
+    fn solved_parts_are_not_sent() {
+        let known = parse_answers("5 2 inputs/day5real.txt 26714516\n").unwrap();
+        let known: Vec<&KnownAnswer> = known.iter().collect();
+
+        assert!(check_submission("26714516", &known, &[], 0).is_err());
+        assert!(check_submission("1234", &known, &[], 0).is_err());
+    }
+
+    #[test]
+    fn waits_for_the_rate_limit() {
+        let log = parse_log("1000 3 1 4361 wrong\n1000 3 1 4362 wait 60\n").unwrap();
+
+        assert!(check_submission("4363", &[], &log, 1030).is_err());
+        assert!(check_submission("4363", &[], &log, 1060).is_ok());
+    }
+}
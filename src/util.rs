@@ -1,50 +1,617 @@
-use num::Num;
-use std::fs::File;
-use std::io::BufReader;
-use std::ops::Deref;
+use crate::error::ParseError;
+use std::fmt;
+use std::ops::{Deref, Index, IndexMut};
 use std::str::FromStr;
 
-pub fn read_file_as_text(path: &str) -> BufReader<File> {
-    let f = File::open(path).unwrap();
-    BufReader::new(f)
+/// Directory of the input files, unless the config file says otherwise
+pub const INPUTS_DIR: &str = "./inputs";
+
+/// Path of one of the input files of a day, like `./inputs/day5test.txt`
+pub fn input_path(dir: &str, day: u8, variant: &str) -> String {
+    format!("{}/day{}{}.txt", dir.trim_end_matches('/'), day, variant)
 }
 
+/// Positions around (x, y) in rows that may have different lengths,
+/// diagonals included
 pub fn generate_adjacencies<T, T2: Deref<Target = [T]>>(
     cube: &[T2],
     x: usize,
     y: usize,
 ) -> Vec<(usize, usize)> {
-    let ix = x as isize;
-    let iy = y as isize;
-
-    [
-        (iy - 1, ix - 1),
-        (iy - 1, ix),
-        (iy - 1, ix + 1),
-        (iy, ix - 1),
-        (iy, ix + 1),
-        (iy + 1, ix - 1),
-        (iy + 1, ix),
-        (iy + 1, ix + 1),
-    ]
-    .into_iter()
-    .filter(|&(ay, ax)| {
-        ay >= 0 && ax >= 0 && ay < (cube.len() as isize) && ax < (cube[0].len() as isize)
+    neighbors_in(cube, (y, x), &Direction::ALL)
+        .map(|(_, pos)| pos)
+        .collect()
+}
+
+/// Direction from a cell to one of its neighbors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// The 4 neighbors that share a side
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The 8 neighbors, diagonals included
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// How much (y, x) change when moving in this direction
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::NorthEast => (-1, 1),
+            Direction::East => (0, 1),
+            Direction::SouthEast => (1, 1),
+            Direction::South => (1, 0),
+            Direction::SouthWest => (1, -1),
+            Direction::West => (0, -1),
+            Direction::NorthWest => (-1, -1),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::NorthEast => Direction::SouthWest,
+            Direction::East => Direction::West,
+            Direction::SouthEast => Direction::NorthWest,
+            Direction::South => Direction::North,
+            Direction::SouthWest => Direction::NorthEast,
+            Direction::West => Direction::East,
+            Direction::NorthWest => Direction::SouthEast,
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        !Direction::ORTHOGONAL.contains(&self)
+    }
+}
+
+/// Iterator over the neighbors of a position, with the direction of each.
+/// `row_width` gives how many cells each row has, so the rows can have
+/// different lengths.
+#[derive(Debug, Clone)]
+pub struct Neighbors<F> {
+    pos: Position,
+    height: usize,
+    row_width: F,
+    directions: std::slice::Iter<'static, Direction>,
+    wrap: bool,
+}
+
+impl<F: Fn(usize) -> usize> Neighbors<F> {
+    pub fn new(
+        pos: Position,
+        height: usize,
+        row_width: F,
+        directions: &'static [Direction],
+    ) -> Neighbors<F> {
+        Neighbors {
+            pos,
+            height,
+            row_width,
+            directions: directions.iter(),
+            wrap: false,
+        }
+    }
+
+    /// Go around the edges, like on a torus: the neighbors past the last
+    /// row or column are in the first one, and the other way around
+    pub fn wrapping(self) -> Neighbors<F> {
+        Neighbors { wrap: true, ..self }
+    }
+
+    fn neighbor(&self, direction: Direction) -> Option<Position> {
+        let (dy, dx) = direction.offset();
+        let y = self.pos.0 as isize + dy;
+        let x = self.pos.1 as isize + dx;
+
+        let y = match self.wrap {
+            true if self.height > 0 => y.rem_euclid(self.height as isize),
+            _ => y,
+        };
+        if y < 0 || y >= self.height as isize {
+            return None;
+        }
+
+        let width = (self.row_width)(y as usize) as isize;
+        let x = match self.wrap {
+            true if width > 0 => x.rem_euclid(width),
+            _ => x,
+        };
+        if x < 0 || x >= width {
+            return None;
+        }
+
+        Some((y as usize, x as usize))
+    }
+}
+
+impl<F: Fn(usize) -> usize> Iterator for Neighbors<F> {
+    type Item = (Direction, Position);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let direction = *self.directions.next()?;
+            if let Some(pos) = self.neighbor(direction) {
+                return Some((direction, pos));
+            }
+        }
+    }
+}
+
+/// Neighbors of a position in rows that may have different lengths
+pub fn neighbors_in<'a, T, R: Deref<Target = [T]>>(
+    rows: &'a [R],
+    pos: Position,
+    directions: &'static [Direction],
+) -> Neighbors<impl Fn(usize) -> usize + 'a> {
+    Neighbors::new(pos, rows.len(), move |y| rows[y].len(), directions)
+}
+
+/// Position in a grid, as (y, x)
+pub type Position = (usize, usize);
+
+/// Rectangular grid, stored row after row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid from its cells, row after row
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "the cells do not fill the grid"
+        );
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse a grid with a cell for each character. Every row must be as wide
+    /// as the first one; `expected` describes the valid cells in the errors.
+    pub fn from_lines(
+        lines: impl Iterator<Item = String>,
+        expected: &str,
+    ) -> Result<Grid<T>, ParseError>
+    where
+        T: TryFrom<char>,
+    {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in lines.enumerate() {
+            let start = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                if width.is_some_and(|width| x >= width) {
+                    return Err(
+                        ParseError::new(x + 1, "end of line", format!("{:?}", c)).at_line(y + 1)
+                    );
+                }
+                let cell = T::try_from(c).map_err(|_| {
+                    ParseError::new(x + 1, expected, format!("{:?}", c)).at_line(y + 1)
+                })?;
+                cells.push(cell);
+            }
+
+            let row_width = cells.len() - start;
+            match width {
+                Some(width) if row_width < width => {
+                    return Err(ParseError::at_end(&line, format!("{} cells", width)).at_line(y + 1))
+                }
+                Some(_) => {}
+                None => width = Some(row_width),
+            }
+            height += 1;
+        }
+
+        Ok(Grid::new(width.unwrap_or(0), height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, (y, x): Position) -> Option<usize> {
+        (y < self.height && x < self.width).then_some(y * self.width + x)
+    }
+
+    /// Cell at a position, or `None` outside of the grid
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        (0..self.height).map(move |y| &self[(y, x)])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every cell with its position, row after row
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| ((i / self.width, i % self.width), cell))
+    }
+
+    /// Position of the first cell (row after row) that matches
+    pub fn find(&self, mut matches: impl FnMut(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find_map(|(pos, cell)| matches(cell).then_some(pos))
+    }
+
+    /// Neighbors of a cell in some directions (`Direction::ORTHOGONAL` or
+    /// `Direction::ALL`), with the direction of each
+    pub fn neighbors(
+        &self,
+        pos: Position,
+        directions: &'static [Direction],
+    ) -> Neighbors<impl Fn(usize) -> usize> {
+        let width = self.width;
+        Neighbors::new(pos, self.height, move |_| width, directions)
+    }
+
+    /// Grid with the rows as columns
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = self.columns().flatten().cloned().collect();
+        Grid::new(self.height, self.width, cells)
+    }
+
+    /// Grid turned a quarter clockwise
+    pub fn rotate(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = self
+            .columns()
+            .flat_map(|column| column.collect::<Vec<_>>().into_iter().rev())
+            .cloned()
+            .collect();
+        Grid::new(self.height, self.width, cells)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pos))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Every integer in `text`, whatever is between them: `x=-3, y=4` gives
+/// `-3` and `4`. A `-` right before the digits is the sign.
+pub fn number_tokens(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        let start = rest.find(|c: char| c.is_ascii_digit())?;
+        let end = rest[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(rest.len(), |len| start + len);
+        let start = match rest[..start].ends_with('-') {
+            true => start - 1,
+            false => start,
+        };
+
+        let token = &rest[start..end];
+        rest = &rest[end..];
+        Some(token)
     })
-    .map(|(ay, ax)| (ay as usize, ax as usize))
-    .collect()
 }
 
-pub fn split_numbers_by_space<T: Num + FromStr>(val: &str) -> Vec<T> {
-    val.trim()
-        .split(' ')
-        .filter_map(|s| s.parse::<T>().ok())
+/// Every integer in `piece`, which must be a slice of `line`, ignoring
+/// anything else. Numbers that do not fit in `T` are errors, and so are
+/// negative numbers when `T` is unsigned.
+pub fn numbers_in<T: FromStr>(
+    line: &str,
+    piece: &str,
+    expected: &str,
+) -> Result<Vec<T>, ParseError> {
+    number_tokens(piece)
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| ParseError::in_piece(line, token, expected))
+        })
         .collect()
 }
 
-pub fn split_numbers_by_comma<T: Num + FromStr>(val: &str) -> Vec<T> {
-    val.trim()
-        .split(',')
-        .filter_map(|s| s.parse::<T>().ok())
+/// Numbers in `piece` (a slice of `line`) split by `separator`, with
+/// optional spaces around them. Each one must be a single token of
+/// `number_tokens`, so anything else is an error at its column; with a space
+/// as the separator, runs of spaces are fine.
+pub fn parse_numbers<T: FromStr>(
+    line: &str,
+    piece: &str,
+    separator: char,
+    expected: &str,
+) -> Result<Vec<T>, ParseError> {
+    piece
+        .split(separator)
+        .map(|token| token.trim())
+        .filter(|token| !(separator.is_whitespace() && token.is_empty()))
+        .map(|token| {
+            let mut numbers = number_tokens(token);
+            match (numbers.next(), numbers.next()) {
+                (Some(number), None) if number == token => token.parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| ParseError::in_piece(line, token, expected))
+        })
         .collect()
 }
+
+/// Numbers after a label, in lines like `Time:      7  15   30`
+pub fn labelled_numbers<T: FromStr>(
+    line: &str,
+    label: &str,
+    expected: &str,
+) -> Result<Vec<T>, ParseError> {
+    let numbers = line
+        .strip_prefix(label)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .ok_or_else(|| ParseError::in_piece(line, line, format!("\"{}:\"", label)))?;
+
+    parse_numbers(line, numbers, ' ', expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacencies_in_the_middle() {
+        let grid = vec![vec![0; 3]; 3];
+        let mut adj = generate_adjacencies(&grid, 1, 1);
+        adj.sort();
+
+        assert_eq!(
+            adj,
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn adjacencies_skip_the_borders() {
+        let grid = vec![vec![0; 4]; 3];
+        let mut adj = generate_adjacencies(&grid, 0, 0);
+        adj.sort();
+        assert_eq!(adj, vec![(0, 1), (1, 0), (1, 1)]);
+
+        // the coordinates are (y, x), and the grid is wider than it is tall
+        let mut adj = generate_adjacencies(&grid, 3, 2);
+        adj.sort();
+        assert_eq!(adj, vec![(1, 2), (1, 3), (2, 2)]);
+    }
+
+    fn grid(text: &str) -> Result<Grid<char>, ParseError> {
+        Grid::from_lines(text.lines().map(String::from), "a character")
+    }
+
+    #[test]
+    fn grids_are_parsed_and_shown_again() {
+        let g = grid("abc\ndef").unwrap();
+
+        assert_eq!((g.width(), g.height()), (3, 2));
+        assert_eq!(g[(1, 0)], 'd');
+        assert_eq!(g.get((0, 3)), None);
+        assert_eq!(g.get((2, 0)), None);
+        assert_eq!(g.find(|&c| c == 'e'), Some((1, 1)));
+        assert_eq!(g.column(2).collect::<String>(), "cf");
+        assert_eq!(g.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn grid_rows_are_as_wide_as_the_first() {
+        let e = grid("abc\nde").unwrap_err();
+        assert_eq!((e.line, e.column, e.found.as_str()), (2, 3, "end of line"));
+
+        let e = grid("abc\ndefg").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 4));
+    }
+
+    #[test]
+    fn grids_are_transposed_and_rotated() {
+        let g = grid("abc\ndef").unwrap();
+
+        assert_eq!(g.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(g.rotate().to_string(), "da\neb\nfc");
+        assert_eq!(g.rotate().rotate().rotate().rotate(), g);
+    }
+
+    #[test]
+    fn neighbors_with_their_directions() {
+        let g = grid("abc\ndef\nghi").unwrap();
+        let around = |pos, directions| {
+            g.neighbors(pos, directions)
+                .map(|(d, pos)| (d, g[pos]))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            around((0, 1), &Direction::ORTHOGONAL),
+            vec![
+                (Direction::East, 'c'),
+                (Direction::South, 'e'),
+                (Direction::West, 'a')
+            ]
+        );
+        assert_eq!(around((1, 1), &Direction::ALL).len(), 8);
+        assert_eq!(around((2, 2), &Direction::ALL).len(), 3);
+    }
+
+    #[test]
+    fn neighbors_wrap_around_the_edges() {
+        let g = grid("abc\ndef\nghi").unwrap();
+        let wrapped: String = g
+            .neighbors((0, 0), &Direction::ORTHOGONAL)
+            .wrapping()
+            .map(|(_, pos)| g[pos])
+            .collect();
+
+        assert_eq!(wrapped, "gbdc");
+    }
+
+    #[test]
+    fn neighbors_in_ragged_rows() {
+        let rows = vec![vec![0; 1], vec![0; 3], vec![0; 2]];
+        let around: Vec<Position> = neighbors_in(&rows, (1, 2), &Direction::ALL)
+            .map(|(_, pos)| pos)
+            .collect();
+        assert_eq!(around, vec![(2, 1), (1, 1)]);
+
+        // the last cell of the middle row is under the first one of the others
+        let wrapped: Vec<Position> = neighbors_in(&rows, (1, 2), &Direction::ORTHOGONAL)
+            .wrapping()
+            .map(|(_, pos)| pos)
+            .collect();
+        assert_eq!(wrapped, vec![(0, 0), (1, 0), (2, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn numbers_with_anything_between_them() {
+        let tokens: Vec<&str> = number_tokens("x=-3, y=4 -> 10-2").collect();
+        assert_eq!(tokens, vec!["-3", "4", "10", "-2"]);
+
+        let line = "1 99999999999";
+        assert_eq!(
+            numbers_in::<u64>(line, line, "a number").unwrap(),
+            vec![1, 99999999999]
+        );
+        let e = numbers_in::<u32>(line, line, "a number").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (3, "\"99999999999\""));
+        let e = numbers_in::<u32>("x=-3", "x=-3", "a number").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (3, "\"-3\""));
+    }
+
+    #[test]
+    fn strict_numbers_show_where_the_junk_is() {
+        let line = " 41 48  83 ";
+        assert_eq!(
+            parse_numbers::<u32>(line, line, ' ', "a number").unwrap(),
+            vec![41, 48, 83]
+        );
+
+        let line = "1,-2, 3";
+        assert_eq!(
+            parse_numbers::<i64>(line, line, ',', "a number").unwrap(),
+            vec![1, -2, 3]
+        );
+
+        let line = "1 2x 3";
+        let e = parse_numbers::<u32>(line, line, ' ', "a number").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (3, "\"2x\""));
+
+        let line = "1,,3";
+        let e = parse_numbers::<u32>(line, line, ',', "a number").unwrap_err();
+        assert_eq!((e.column, e.found.as_str()), (3, "','"));
+
+        for (line, column) in [("1 +2", 3), ("1 2-3", 3), ("1 -2", 3), ("- 1", 1)] {
+            let e = parse_numbers::<u32>(line, line, ' ', "a number").unwrap_err();
+            assert_eq!(e.column, column, "{}", line);
+        }
+        let line = "10 -3 -0";
+        assert_eq!(
+            parse_numbers::<i8>(line, line, ' ', "a number").unwrap(),
+            vec![10, -3, 0]
+        );
+    }
+
+    #[test]
+    fn numbers_after_a_label() {
+        assert_eq!(
+            labelled_numbers::<u32>("Time:      7  15   30", "Time", "a time").unwrap(),
+            vec![7, 15, 30]
+        );
+
+        let e = labelled_numbers::<u32>("Tiem: 7", "Time", "a time").unwrap_err();
+        assert_eq!((e.column, e.expected.as_str()), (1, "\"Time:\""));
+        let e = labelled_numbers::<u32>("Time: 7 1O", "Time", "a time").unwrap_err();
+        assert_eq!(e.column, 9);
+    }
+}
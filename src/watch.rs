@@ -0,0 +1,153 @@
+use inotify::{Inotify, WatchMask};
+use serde_json::Value;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Editors usually write a file in more than one step, so wait a bit for
+/// the other events before running
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+const VARIANTS: [&str; 2] = ["test", "real"];
+
+/// If a change in `name` (a file in `src/` or in the inputs) is about the day
+fn is_about_day(name: &str, day: u8) -> bool {
+    let prefix = format!("day{}", day);
+
+    match name.strip_prefix(&prefix) {
+        Some(".rs") => true,
+        // day1test.txt, but not day12test.txt
+        Some(rest) => rest.ends_with(".txt") && !rest.starts_with(|c: char| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+fn format_part(part: &Value) -> String {
+    let answer = match (&part["answer"], part["status"].as_str()) {
+        (Value::Null, Some(status)) => format!("({})", status),
+        (Value::String(answer), _) => answer.clone(),
+        (answer, _) => answer.to_string(),
+    };
+
+    format!(
+        "part {}: {:<16} {:>10.3}ms",
+        part["part"],
+        answer,
+        part["solve_time_ms"].as_f64().unwrap_or_default()
+    )
+}
+
+/// Build and run the day in another process, so changes in the source are
+/// picked up, and print a line with the answers and timings
+fn run_variant(root: &Path, day: u8, variant: &str) {
+    let mut cargo = Command::new("cargo");
+    cargo.args(["run", "--quiet"]);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    cargo
+        .args(["--", &day.to_string(), "--variant", variant])
+        .args(["--format", "json", "--quiet"])
+        .current_dir(root);
+
+    let output = match cargo.output() {
+        Ok(output) => output,
+        Err(e) => {
+            println!("{:<6} could not run cargo: {}", variant, e);
+            return;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match serde_json::from_str::<Value>(stdout.trim()) {
+        Ok(run) => {
+            let parts: Vec<String> = run["parts"]
+                .as_array()
+                .map(|parts| parts.iter().map(format_part).collect())
+                .unwrap_or_default();
+
+            println!("{:<6} {}", variant, parts.join("   "));
+            if let Some(error) = run["error"].as_str() {
+                println!("       {}", error);
+            }
+        }
+        // most likely the build failed, and cargo said why
+        Err(_) => {
+            println!("{:<6} failed:", variant);
+            print!("{}", String::from_utf8_lossy(&output.stderr));
+        }
+    }
+}
+
+fn run_all_variants(root: &Path, day: u8) {
+    for variant in VARIANTS {
+        run_variant(root, day, variant);
+    }
+}
+
+/// Run a day on the example and real inputs, and again whenever its module
+/// or one of its inputs (in `inputs`) changes. `root` has `src/` and the
+/// `aoc.toml` used by the runs. Only returns on errors.
+pub fn watch(root: &Path, inputs: &Path, day: u8) -> io::Result<()> {
+    let mut inotify = Inotify::init()?;
+    let mask = WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE;
+
+    // the directories are watched, and not the files, because editors
+    // often replace the file instead of writing to it
+    for dir in [&root.join("src"), inputs] {
+        inotify.watches().add(dir, mask)?;
+    }
+
+    println!(
+        "watching src/day{}.rs and {}/day{}*.txt, Ctrl+C to stop\n",
+        day,
+        inputs.display(),
+        day
+    );
+    run_all_variants(root, day);
+
+    let mut buffer = [0; 4096];
+    loop {
+        let mut changed: Vec<String> = inotify
+            .read_events_blocking(&mut buffer)?
+            .filter_map(|e| e.name.map(|n| n.to_string_lossy().to_string()))
+            .collect();
+
+        thread::sleep(SETTLE_TIME);
+        match inotify.read_events(&mut buffer) {
+            Ok(events) => changed
+                .extend(events.filter_map(|e| e.name.map(|n| n.to_string_lossy().to_string()))),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e),
+        }
+
+        changed.retain(|name| is_about_day(name, day));
+        changed.sort();
+        changed.dedup();
+
+        if !changed.is_empty() {
+            println!("\n--- {} changed", changed.join(", "));
+            run_all_variants(root, day);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_files_of_the_day_are_watched() {
+        assert!(is_about_day("day1.rs", 1));
+        assert!(is_about_day("day1test.txt", 1));
+        assert!(is_about_day("day1real.txt", 1));
+        assert!(is_about_day("day1test2.txt", 1));
+
+        assert!(!is_about_day("day12.rs", 1));
+        assert!(!is_about_day("day12test.txt", 1));
+        assert!(!is_about_day("day1.rs.swp", 1));
+        assert!(!is_about_day("main.rs", 1));
+    }
+}
@@ -0,0 +1,23 @@
+//! Every part with more than one strategy gives the same answer with all of
+//! them, on small generated inputs.
+
+use aoc2023::crosscheck::{checked_parts, crosscheck};
+use aoc2023::registry;
+
+#[test]
+fn strategies_agree() {
+    for solver in registry() {
+        for part in checked_parts(solver.as_ref()) {
+            if let Err(d) = crosscheck(solver.as_ref(), part, 30, 3, 0) {
+                panic!(
+                    "day {} part {} with seed {}: {:?}\n{}",
+                    d.day,
+                    d.part,
+                    d.seed,
+                    d.answers,
+                    d.input.join("\n")
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use aoc2023::day11::GalacticMap;
+use aoc2023::day5::SeedFile;
+use aoc2023::registry;
+
+fn example(day: u8) -> impl Iterator<Item = String> {
+    let text = std::fs::read_to_string(format!("inputs/day{}test.txt", day)).unwrap();
+    text.lines()
+        .map(String::from)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+#[test]
+fn registry_is_sorted_by_day() {
+    let days: Vec<u8> = registry().iter().map(|s| s.day()).collect();
+
+    assert!(days.windows(2).all(|w| w[0] < w[1]));
+    assert!((1..=12).all(|d| days.contains(&d)));
+}
+
+#[test]
+fn models_can_be_used_directly() {
+    let seeds = SeedFile::from_lines(example(5)).unwrap();
+    assert_eq!(seeds.seed_to_location(79), 82);
+
+    let galaxies = GalacticMap::find_galaxies(example(11)).unwrap();
+    let map = GalacticMap::from_galaxies(2, &galaxies);
+    assert_eq!(map.find_distance(5, 9).2, 9);
+}